@@ -0,0 +1,160 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use anyhow::{bail, ensure, Context, Result};
+
+use crate::solution::{Part, Solution};
+
+// The recorded answers to each day's embedded input, one tab-separated line per day. A day that
+// has been scaffolded but not solved yet is listed without any answers; otherwise every part the
+// day implements must have an answer.
+pub const ANSWERS: &str = include_str!("../answers.txt");
+
+// Expected answers, keyed by day and then part.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Answers {
+    answers: BTreeMap<u32, BTreeMap<Part, String>>,
+}
+
+impl Answers {
+    pub fn recorded() -> Answers {
+        Answers::parse(ANSWERS).expect("Invalid answers.txt")
+    }
+
+    pub fn parse(s: &str) -> Result<Answers> {
+        let mut answers = BTreeMap::new();
+        for line in s.lines().filter(|l| !l.trim().is_empty() && !l.starts_with('#')) {
+            let mut fields = line.split('\t');
+            let day: u32 = fields.next().expect("Non-empty").parse()
+                .with_context(|| format!("Invalid day in '{}'", line))?;
+            let parts: BTreeMap<_, _> = Part::ALL.iter().zip(&mut fields)
+                .filter(|(_, answer)| !answer.is_empty())
+                .map(|(p, answer)| (*p, answer.to_string()))
+                .collect();
+            ensure!(fields.next().is_none(), "Too many answers for day {}: '{}'", day, line);
+            if answers.insert(day, parts).is_some() {
+                bail!("Duplicate answers for day {}", day);
+            }
+        }
+        Ok(Answers{ answers })
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        self.answers.get(&day).and_then(|parts| parts.get(&part)).map(|a| a.as_str())
+    }
+
+    // True if the day is listed without any answers, as `advent new` registers it
+    pub fn in_progress(&self, day: u32) -> bool {
+        self.answers.get(&day).map(|parts| parts.is_empty()).unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Outcome {
+    Correct,
+    Incorrect{ expected: String, actual: String },
+    // No answer has been recorded for this part
+    Unrecorded(String),
+    // The day is still being worked on, so no answers are expected yet
+    InProgress(String),
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Outcome::Incorrect{..} | Outcome::Unrecorded(_))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "ok"),
+            Outcome::Incorrect{ expected, actual } => write!(f, "FAILED, expected {} but was {}", expected, actual),
+            Outcome::Unrecorded(actual) => write!(f, "FAILED, no recorded answer for {}", actual),
+            Outcome::InProgress(actual) => write!(f, "{} (in progress)", actual),
+        }
+    }
+}
+
+// Solves each part of S against its embedded input and compares the results to the answers.
+pub fn check_against<S: Solution>(answers: &Answers) -> Result<Vec<(Part, Outcome)>> {
+    let input = S::parse(S::INPUT)?;
+    Part::ALL.iter().filter(|p| p.exists_for::<S>()).map(|p| {
+        let actual = p.solve::<S>(&input)?;
+        let outcome = match answers.get(S::DAY, *p) {
+            Some(expected) if expected == actual => Outcome::Correct,
+            Some(expected) => Outcome::Incorrect{ expected: expected.to_string(), actual },
+            None if answers.in_progress(S::DAY) => Outcome::InProgress(actual),
+            None => Outcome::Unrecorded(actual),
+        };
+        Ok((*p, outcome))
+    }).collect()
+}
+
+pub fn check<S: Solution>() -> Result<Vec<(Part, Outcome)>> {
+    check_against::<S>(&Answers::recorded())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Example;
+
+    impl Solution for Example {
+        const DAY: u32 = 3;
+        const INPUT: &'static str = "2\n3\n4\n";
+
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            input.lines().map(|l| Ok(l.parse()?)).collect()
+        }
+
+        fn part1(input: &Self::Input) -> Result<String> {
+            Ok(input.iter().sum::<u32>().to_string())
+        }
+
+        fn part2(input: &Self::Input) -> Result<String> {
+            Ok(input.iter().product::<u32>().to_string())
+        }
+    }
+
+    #[test]
+    fn recorded() {
+        let answers = Answers::recorded();
+        assert_eq!(answers.get(1, Part::One), Some("53921"));
+        assert_eq!(answers.get(25, Part::Two), None);
+        assert_eq!(answers.get(16, Part::One), None);
+        assert!(!answers.in_progress(16));
+    }
+
+    parameterized_test::create!{ outcomes, (answers, part1, part2), {
+        let answers = Answers::parse(answers).unwrap();
+        assert_eq!(check_against::<Example>(&answers).unwrap(), [(Part::One, part1), (Part::Two, part2)]);
+    }}
+    outcomes! {
+        correct: ("03\t9\t24", Outcome::Correct, Outcome::Correct),
+        incorrect: ("03\t9\t25", Outcome::Correct,
+            Outcome::Incorrect{ expected: "25".into(), actual: "24".into() }),
+        partial: ("# comment\n03\t9\n", Outcome::Correct, Outcome::Unrecorded("24".into())),
+        missing: ("01\t9\t24", Outcome::Unrecorded("9".into()), Outcome::Unrecorded("24".into())),
+        in_progress: ("03\n", Outcome::InProgress("9".into()), Outcome::InProgress("24".into())),
+    }
+
+    #[test]
+    fn failures() {
+        assert!(!Outcome::Correct.is_failure());
+        assert!(Outcome::Incorrect{ expected: "25".into(), actual: "24".into() }.is_failure());
+        assert!(Outcome::Unrecorded("24".into()).is_failure());
+        assert!(!Outcome::InProgress("24".into()).is_failure());
+    }
+
+    parameterized_test::create!{ invalid, answers, {
+        assert!(Answers::parse(answers).is_err());
+    }}
+    invalid! {
+        bad_day: "three\t9\t24",
+        too_many: "03\t9\t24\t1",
+        duplicate: "03\t9\n03\t9\t24",
+    }
+}
@@ -0,0 +1,208 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use anyhow::{ensure, Result};
+
+use crate::solution::{Part, Solution};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    // Untimed runs before sampling begins, e.g. to populate caches and page in the input
+    pub warmup: u32,
+    pub samples: u32,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config{ warmup: 1, samples: 3 }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub mean: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty());
+        let mean = samples.iter().sum::<Duration>() / samples.len() as u32;
+        let min = *samples.iter().min().expect("Non-empty");
+        let max = *samples.iter().max().expect("Non-empty");
+        Stats{ mean, min, max }
+    }
+}
+
+// Times repeated invocations of f, failing if any invocation does.
+pub fn measure<T>(config: &Config, mut f: impl FnMut() -> Result<T>) -> Result<Stats> {
+    ensure!(config.samples > 0, "Must take at least one sample");
+    for _ in 0..config.warmup {
+        black_box(f()?);
+    }
+    let mut samples = Vec::with_capacity(config.samples as usize);
+    for _ in 0..config.samples {
+        let start = Instant::now();
+        black_box(f()?);
+        samples.push(start.elapsed());
+    }
+    Ok(Stats::from_samples(&samples))
+}
+
+#[derive(Debug)]
+pub struct Report {
+    pub day: u32,
+    pub parse: Stats,
+    pub parts: Vec<(Part, Stats)>,
+    pub alternatives: Vec<(Part, &'static str, Stats)>,
+}
+
+// Benchmarks parsing the input, each part, and each of the day's alternative implementations.
+pub fn bench<S: Solution>(input: &str, config: &Config) -> Result<Report> {
+    let parse = measure(config, || S::parse(input))?;
+    let parsed = S::parse(input)?;
+    let parts = Part::ALL.iter()
+        .filter(|p| p.exists_for::<S>())
+        .map(|p| Ok((*p, measure(config, || p.solve::<S>(&parsed))?)))
+        .collect::<Result<_>>()?;
+    let alternatives = S::alternatives().into_iter()
+        .map(|a| Ok((a.part, a.name, measure(config, || (a.solve)(&parsed))?)))
+        .collect::<Result<_>>()?;
+    Ok(Report{ day: S::DAY, parse, parts, alternatives })
+}
+
+pub const SUMMARY_COLUMNS: &[&str] = &["Day", "Binary Size", "Runtime", "Accumulated"];
+pub const STAGE_COLUMNS: &[&str] = &["Day", "Stage", "Mean", "Min", "Max"];
+pub const ALTERNATIVE_COLUMNS: &[&str] = &["Day", "Part", "Implementation", "Mean", "Min", "Max"];
+
+impl Report {
+    // The mean time to parse the input and solve all parts
+    pub fn runtime(&self) -> Duration {
+        self.parse.mean + self.parts.iter().map(|(_, s)| s.mean).sum::<Duration>()
+    }
+
+    pub fn summary_row(&self, binary_size: Option<u64>, accumulated: Duration) -> String {
+        markdown_row(&[
+            self.day.to_string(),
+            binary_size.map(|b| format!("{}KB", b / 1024)).unwrap_or_else(|| "-".into()),
+            fmt_duration(self.runtime()),
+            fmt_duration(accumulated),
+        ])
+    }
+
+    // Rows breaking the runtime down into parsing and each part.
+    pub fn stage_rows(&self) -> Vec<String> {
+        let row = |stage: &str, stats: &Stats| markdown_row(&[
+            self.day.to_string(), stage.into(),
+            fmt_duration(stats.mean), fmt_duration(stats.min), fmt_duration(stats.max)]);
+        std::iter::once(row("Parse", &self.parse))
+            .chain(self.parts.iter().map(|(part, stats)| row(&part.to_string(), stats)))
+            .collect()
+    }
+
+    // Rows comparing each part that has alternatives to its primary implementation.
+    pub fn alternative_rows(&self) -> Vec<String> {
+        let row = |part: Part, name: &str, stats: &Stats| markdown_row(&[
+            self.day.to_string(), part.to_string(), name.into(),
+            fmt_duration(stats.mean), fmt_duration(stats.min), fmt_duration(stats.max)]);
+        let mut rows = Vec::new();
+        for (part, stats) in &self.parts {
+            let alternatives: Vec<_> = self.alternatives.iter().filter(|(p, _, _)| p == part).collect();
+            if alternatives.is_empty() { continue; }
+            rows.push(row(*part, "(primary)", stats));
+            rows.extend(alternatives.iter().map(|(p, name, stats)| row(*p, name, stats)));
+        }
+        rows
+    }
+}
+
+fn fmt_duration(duration: Duration) -> String {
+    format!("{:.3?}", duration)
+}
+
+fn markdown_row(cells: &[String]) -> String {
+    cells.iter().map(|c| format!("| {:>12} ", c)).collect::<String>() + "|"
+}
+
+pub fn markdown_header(columns: &[&str]) -> String {
+    let columns: Vec<_> = columns.iter().map(|c| c.to_string()).collect();
+    format!("{}\n{}|", markdown_row(&columns), "|--------------".repeat(columns.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Alternative;
+
+    struct Example;
+
+    impl Solution for Example {
+        const DAY: u32 = 3;
+        const INPUT: &'static str = "2\n3\n4\n";
+
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            input.lines().map(|l| Ok(l.parse()?)).collect()
+        }
+
+        fn part1(input: &Self::Input) -> Result<String> {
+            Ok(input.iter().sum::<u32>().to_string())
+        }
+
+        fn part2(input: &Self::Input) -> Result<String> {
+            Ok(input.iter().product::<u32>().to_string())
+        }
+
+        fn alternatives() -> Vec<Alternative<Self::Input>> {
+            vec![Alternative{ part: Part::Two, name: "Reversed", solve: |input| Ok(input.iter().rev().product::<u32>().to_string()) }]
+        }
+    }
+
+    #[test]
+    fn stats() {
+        let samples = [Duration::from_millis(3), Duration::from_millis(1), Duration::from_millis(5)];
+        assert_eq!(Stats::from_samples(&samples),
+                   Stats{ mean: Duration::from_millis(3), min: Duration::from_millis(1), max: Duration::from_millis(5) });
+    }
+
+    #[test]
+    fn measure_runs() {
+        let mut runs = 0;
+        measure(&Config{ warmup: 2, samples: 3 }, || { runs += 1; Ok(()) }).unwrap();
+        assert_eq!(runs, 5);
+
+        assert!(measure(&Config{ warmup: 0, samples: 0 }, || Ok(())).is_err());
+        assert!(measure(&Config::default(), || anyhow::bail!("Failed") as Result<()>).is_err());
+    }
+
+    #[test]
+    fn report() {
+        let report = bench::<Example>(Example::INPUT, &Config{ warmup: 0, samples: 1 }).unwrap();
+        assert_eq!(report.day, 3);
+        assert_eq!(report.parts.iter().map(|(p, _)| *p).collect::<Vec<_>>(), Part::ALL);
+        assert_eq!(report.alternatives.iter().map(|(p, n, _)| (*p, *n)).collect::<Vec<_>>(), [(Part::Two, "Reversed")]);
+
+        let summary = report.summary_row(Some(2048), report.runtime());
+        assert!(summary.starts_with("|            3 |          2KB |"), "{}", summary);
+        assert_eq!(summary.matches('|').count(), SUMMARY_COLUMNS.len() + 1);
+        assert!(report.summary_row(None, report.runtime()).starts_with("|            3 |            - |"));
+        let stages = report.stage_rows();
+        assert_eq!(stages.len(), 3);
+        assert!(stages[0].starts_with("|            3 |        Parse |"), "{}", stages[0]);
+        assert!(stages[1].starts_with("|            3 |       Part 1 |"), "{}", stages[1]);
+        assert!(stages[2].starts_with("|            3 |       Part 2 |"), "{}", stages[2]);
+        assert_eq!(stages[0].matches('|').count(), STAGE_COLUMNS.len() + 1);
+
+        let alternatives = report.alternative_rows();
+        assert_eq!(alternatives.len(), 2);
+        assert!(alternatives[0].starts_with("|            3 |       Part 2 |    (primary) |"), "{}", alternatives[0]);
+        assert!(alternatives[1].starts_with("|            3 |       Part 2 |     Reversed |"), "{}", alternatives[1]);
+    }
+
+    #[test]
+    fn header() {
+        assert_eq!(markdown_header(&["Day", "Runtime"]),
+                   "|          Day |      Runtime |\n|--------------|--------------|");
+    }
+}
@@ -3,6 +3,8 @@ use anyhow::*;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use advent_2023::solution::{run, Solution};
+
 const DIGITS: &[&str] = &["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
 const WORDS: &[&str] = &["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
 
@@ -14,12 +16,30 @@ static WORDS_RE: Lazy<Regex> = Lazy::new(||
     Regex::new(&format!("{}|{}", DIGITS.join("|"), WORDS.join("|"))).unwrap());
 
 fn main() -> Result<()> {
-    let digit_sum: u32 = include_str!("input.txt").lines().map(|l| extract_number(l, &DIGITS_RE).unwrap()).sum();
-    println!("Initial Calibration Sum: {}", digit_sum);
-    let word_sum: u32 = include_str!("input.txt").lines().map(|l| extract_number(l, &WORDS_RE).unwrap()).sum();
-    println!("Updated Calibration Sum: {}", word_sum);
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 1;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Vec<String>;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.lines().map(|l| l.to_string()).collect())
+    }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        let digit_sum = input.iter().map(|l| extract_number(l, &DIGITS_RE)).sum::<Result<u32>>()?;
+        Ok(digit_sum.to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        let word_sum = input.iter().map(|l| extract_number(l, &WORDS_RE)).sum::<Result<u32>>()?;
+        Ok(word_sum.to_string())
+    }
 }
 
 fn tail_find<'a>(line: &'a str, re: &Regex) -> Option<&'a str> {
@@ -43,6 +63,8 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
     use std::process::Command;
+    use advent_2023::answers::Answers;
+    use advent_2023::solution::Part;
 
     fn extract_numbers(s: &str, re: &Regex) -> Result<Vec<u32>> {
         s.lines().map(|l| extract_number(l, re)).collect()
@@ -61,7 +83,6 @@ mod tests {
 
     #[test]
     fn bash_solution() {
-        // TODO pull this into a util
         let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/bin/01");
         let res = Command::new("bash")
             .arg(dir.join("01.sh"))
@@ -70,6 +91,8 @@ mod tests {
 
         assert!(res.status.success());
         assert!(res.stderr.is_empty());
-        assert_eq!(String::from_utf8(res.stdout).unwrap(), "Part 1:\t53921\nPart 2:\t54676\n");
+        let answers = Answers::recorded();
+        let expected = Part::ALL.iter().map(|p| format!("{}:\t{}\n", p, answers.get(1, *p).unwrap())).collect::<String>();
+        assert_eq!(String::from_utf8(res.stdout).unwrap(), expected);
     }
 }
@@ -2,15 +2,33 @@ use std::str::FromStr;
 use anyhow::*;
 use lazy_regex::regex_captures;
 
+use advent_2023::solution::{run, Solution};
+
 const BAG: Tiles =  Tiles{red: 12, green: 13, blue: 14 };
 
 fn main() -> Result<()> {
-    let input = parse_input(include_str!("input.txt"))?;
-    let sum_ids: u32 = input.iter().filter(|g| g.is_valid_game_for(&BAG)).map(|g| g.id).sum();
-    println!("Valid Games: {}", sum_ids);
-    let sum_power: u32 = input.iter().map(|g| g.min_cubes().power()).sum();
-    println!("Game Power: {:?}",sum_power);
-    Ok(())
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 2;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        let sum_ids: u32 = input.iter().filter(|g| g.is_valid_game_for(&BAG)).map(|g| g.id).sum();
+        Ok(sum_ids.to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        let sum_power: u32 = input.iter().map(|g| g.min_cubes().power()).sum();
+        Ok(sum_power.to_string())
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -45,7 +63,7 @@ impl FromStr for Tiles {
 }
 
 #[derive(Debug)]
-struct Game {
+pub struct Game {
     id: u32,
     hands: Vec<Tiles>,
 }
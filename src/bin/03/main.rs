@@ -3,15 +3,39 @@ use std::str::FromStr;
 use anyhow::*;
 use lazy_regex::regex;
 
-use advent_2023::euclid::{bounds, Bounds, Point, point};
+use advent_2023::euclid::{bounds, Bounds, Grid, Point, point, SpatialIndex};
+use advent_2023::solution::{run, Alternative, Part as SolutionPart, Solution};
 
 fn main() -> Result<()> {
-    let schematic: Schematic = include_str!("input.txt").parse()?;
-    println!("Part number sum: {}", schematic.valid_part_ids().iter().sum::<u32>());
-    println!("Gear ratios product: {}", schematic.all_gears().values()
-        .map(|v| v.iter().product::<u32>()).sum::<u32>());
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 3;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Schematic;
+
+    fn parse(input: &str) -> Result<Self::Input> { input.parse() }
+
+    fn part1(schematic: &Self::Input) -> Result<String> {
+        Ok(schematic.valid_part_ids(Schematic::valid_part_indexed).iter().sum::<u32>().to_string())
+    }
+
+    fn part2(schematic: &Self::Input) -> Result<String> {
+        Ok(schematic.all_gears().values().map(|v| v.iter().product::<u32>()).sum::<u32>().to_string())
+    }
 
-    Ok(())
+    fn alternatives() -> Vec<Alternative<Self::Input>> {
+        vec![
+            Alternative{ part: SolutionPart::One, name: "Any symbol", solve: |schematic|
+                Ok(schematic.valid_part_ids(Schematic::valid_part_any_symbol).iter().sum::<u32>().to_string()) },
+            Alternative{ part: SolutionPart::One, name: "Each symbol", solve: |schematic|
+                Ok(schematic.valid_part_ids(Schematic::valid_part_each_symbol).iter().sum::<u32>().to_string()) },
+        ]
+    }
 }
 
 #[derive(Debug)]
@@ -21,51 +45,42 @@ struct Part {
 }
 
 #[derive(Debug)]
-struct Schematic {
+pub struct Schematic {
     parts: Vec<Part>,
-    symbols: HashMap<Point, char>,
+    symbols: Grid<Option<char>>,
+    symbol_index: SpatialIndex<char>,
 }
 
 impl Schematic {
-    #[allow(dead_code)]
     fn valid_part_any_symbol(&self, part: &Part) -> bool {
-        for y in part.pos.min.y-1..=part.pos.max.y+1 {
-            for x in part.pos.min.x-1..=part.pos.max.x+1 {
-                let p = point(x, y);
-                if self.symbols.contains_key(&p) {
-                    return true;
-                }
-            }
-        }
-        false
+        part.pos.expand(1).iter().any(|p| matches!(self.symbols.get(p), Some(Some(_))))
     }
 
     fn valid_part_each_symbol(&self, part: &Part) -> bool {
-        let adjacent = bounds(point(part.pos.min.x-1, part.pos.min.y-1), point(part.pos.max.x+1, part.pos.max.y+1));
-        for symbol in self.symbols.keys() {
-            if adjacent.contains(*symbol) {
+        let adjacent = part.pos.expand(1);
+        for (symbol, _) in self.symbol_index.iter() {
+            if adjacent.contains(symbol.min) {
                 return true;
             }
         }
         false
     }
 
-    // Neither approach is really optimal, but using in_bounds() on each symbol benchmarks faster
-    // than a linear search for nearby symbols even though it's O(n*m) vs. O(n)
-    fn valid_part_ids(&self) -> Vec<u32> {
-        self.parts.iter().filter(|p| self.valid_part_each_symbol(p)).map(|p| p.id).collect()
+    // Checking each symbol is O(n*m), but benchmarked faster than a linear search for nearby
+    // symbols; the spatial index only has to check symbols in nearby cells.
+    fn valid_part_indexed(&self, part: &Part) -> bool {
+        self.symbol_index.any_within(part.pos.expand(1))
+    }
+
+    fn valid_part_ids(&self, valid: fn(&Schematic, &Part) -> bool) -> Vec<u32> {
+        self.parts.iter().filter(|p| valid(self, p)).map(|p| p.id).collect()
     }
 
     fn all_gears(&self) -> HashMap<Point, Vec<u32>> {
         let mut gears: HashMap<Point, Vec<u32>> = HashMap::new();
         for part in &self.parts {
-            for y in part.pos.min.y-1..=part.pos.max.y+1 {
-                for x in part.pos.min.x - 1..=part.pos.max.x + 1 {
-                    let p = point(x, y);
-                    if self.symbols.get(&p) == Some(&'*') {
-                        gears.entry(p).and_modify(|v| v.push(part.id)).or_insert(vec!(part.id));
-                    }
-                }
+            for (symbol, _) in self.symbol_index.query(part.pos.expand(1)).filter(|(_, c)| **c == '*') {
+                gears.entry(symbol.min).and_modify(|v| v.push(part.id)).or_insert(vec!(part.id));
             }
         }
         gears.into_iter().filter(|(_, v)| v.len() == 2).collect()
@@ -78,7 +93,6 @@ impl FromStr for Schematic {
     fn from_str(s: &str) -> Result<Self> {
         let number_re = regex!(r"\d+");
         let mut parts = Vec::new();
-        let mut symbols = HashMap::new();
         for (y, line) in s.lines().enumerate() {
             let y = y as i32;
             for m in number_re.captures_iter(line).map(|c| c.get(0).expect("0-match")) {
@@ -88,14 +102,13 @@ impl FromStr for Schematic {
                 };
                 parts.push(part);
             }
-            for (x, c) in line.chars().enumerate() {
-                let x = x as i32;
-                if c != '.' && !c.is_ascii_digit() {
-                    symbols.insert(point(x, y), c);
-                }
-            }
         }
-        Ok(Schematic{ parts, symbols })
+        let symbols = Grid::parse(s, |c| Ok(if c != '.' && !c.is_ascii_digit() { Some(c) } else { None }))?;
+        let mut symbol_index = SpatialIndex::new(4);
+        for (pos, c) in symbols.iter().filter_map(|(p, c)| c.map(|c| (p, c))) {
+            symbol_index.insert_point(pos, c);
+        }
+        Ok(Schematic{ parts, symbols, symbol_index })
     }
 }
 
@@ -109,7 +122,10 @@ mod tests {
     #[test]
     fn part_numbers() {
         let schematic: Schematic = include_str!("example.txt").parse().unwrap();
-        assert_eq!(schematic.valid_part_ids(), [467, 35, 633, 617, 592, 755, 664, 598]);
+        let expected = [467, 35, 633, 617, 592, 755, 664, 598];
+        assert_eq!(schematic.valid_part_ids(Schematic::valid_part_indexed), expected);
+        assert_eq!(schematic.valid_part_ids(Schematic::valid_part_any_symbol), expected);
+        assert_eq!(schematic.valid_part_ids(Schematic::valid_part_each_symbol), expected);
     }
 
     #[test]
@@ -3,15 +3,33 @@ use std::str::FromStr;
 use anyhow::*;
 use lazy_regex::regex_captures;
 
+use advent_2023::solution::{run, Solution};
+
 fn main() -> Result<()> {
-    let input = parse_input(include_str!("input.txt"))?;
-    println!("Total points: {}", input.iter().map(Card::score).sum::<u32>());
-    println!("Total cards: {}", count_recursive_wins(&input).iter().sum::<u32>());
-    Ok(())
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 4;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        Ok(input.iter().map(Card::score).sum::<u32>().to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        Ok(count_recursive_wins(input).iter().sum::<u32>().to_string())
+    }
 }
 
 #[derive(Debug)]
-struct Card {
+pub struct Card {
     #[allow(dead_code)]
     id: u32,
     win: HashSet<u32>,
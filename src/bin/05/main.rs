@@ -5,12 +5,29 @@ use itertools::Itertools;
 use range_collections::{RangeSet, RangeSet2};
 use range_collections::range_set::RangeSetRange;
 
+use advent_2023::solution::{run, Solution};
+
 fn main() -> Result<()> {
-    let (seeds, almanac) = parse_input(include_str!("input.txt"))?;
-    println!("Min Location: {}", min_location(&seeds,&almanac));
-    println!("Min Range Location: {}", min_location_ranges(&seeds, &almanac));
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 5;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = (Vec<i64>, Almanac);
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1((seeds, almanac): &Self::Input) -> Result<String> {
+        Ok(min_location(seeds, almanac).to_string())
+    }
+
+    fn part2((seeds, almanac): &Self::Input) -> Result<String> {
+        Ok(min_location_ranges(seeds, almanac).to_string())
+    }
 }
 
 #[derive(Debug)]
@@ -33,7 +50,7 @@ impl FromStr for Mapping {
 }
 
 #[derive(Debug)]
-struct Almanac {
+pub struct Almanac {
     mappings: Vec<Vec<Mapping>>,
 }
 
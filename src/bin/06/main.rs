@@ -1,15 +1,32 @@
-// Time:        46     68     98     66
-// Distance:   358   1054   1807   1080
-static INPUT1: &[Race] = &[
-    Race::create(46, 358), Race::create(68, 1054), Race::create(98, 1807), Race::create(66, 1080)];
-static INPUT2: Race = Race::create(46689866, 358105418071080);
-
-fn main() {
-    println!("Wins Product: {}", INPUT1.iter().map(|r| r.count_wins()).product::<u64>());
-    println!("Concated Wins: {}", INPUT2.count_wins());
+use anyhow::*;
+use itertools::Itertools;
+
+use advent_2023::solution::{run, Solution};
+
+fn main() -> Result<()> {
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 6;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = (Vec<Race>, Race);
+
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1((races, _): &Self::Input) -> Result<String> {
+        Ok(races.iter().map(|r| r.count_wins()).product::<u64>().to_string())
+    }
+
+    fn part2((_, race): &Self::Input) -> Result<String> {
+        Ok(race.count_wins().to_string())
+    }
 }
 
-struct Race{
+pub struct Race{
     time: u64,
     target: u64,
 }
@@ -28,6 +45,19 @@ impl Race {
     }
 }
 
+// Returns both the individual races and the single race formed by ignoring the whitespace
+fn parse_input(input: &str) -> Result<(Vec<Race>, Race)> {
+    let (times, targets) = input.lines()
+        .map(|l| l.split_once(':').map(|(_, v)| v).context("Invalid"))
+        .collect_tuple().context("Expected two lines")?;
+    let (times, targets) = (times?, targets?);
+    let races = times.split_whitespace().zip(targets.split_whitespace())
+        .map(|(t, d)| Ok(Race::create(t.parse()?, d.parse()?)))
+        .collect::<Result<Vec<_>>>()?;
+    let concat = |s: &str| s.split_whitespace().collect::<String>().parse::<u64>();
+    Ok((races, Race::create(concat(times)?, concat(targets)?)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -38,6 +68,16 @@ mod tests {
         Race::create(7, 9), Race::create(15, 40), Race::create(30, 200)];
     static EXAMPLE2: Race = Race::create(71530, 940200);
 
+    #[test]
+    fn check_input() { parse_input(include_str!("input.txt")).unwrap(); }
+
+    #[test]
+    fn parse_races() {
+        let (races, race) = parse_input("Time:      7  15   30\nDistance:  9  40  200\n").unwrap();
+        assert_eq!(races.iter().map(|r| (r.time, r.target)).collect::<Vec<_>>(), [(7, 9), (15, 40), (30, 200)]);
+        assert_eq!((race.time, race.target), (EXAMPLE2.time, EXAMPLE2.target));
+    }
+
     #[test]
     fn check_distances() {
         let example1_1 = &EXAMPLE1[0];
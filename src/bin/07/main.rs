@@ -5,16 +5,34 @@ use std::str::FromStr;
 use anyhow::*;
 use itertools::Itertools;
 
+use advent_2023::solution::{run, Solution};
+
 fn main() -> Result<()> {
-    let mut input = parse_input(include_str!("input.txt"))?;
-    input.sort();
-    println!("Winnings: {}", input.iter().enumerate().map(|(i, (_, b))| b*(i as u64+1)).sum::<u64>());
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 7;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Vec<(Hand, u64)>;
 
-    let mut input = parse_input(&jacks_wild(include_str!("input.txt")))?;
-    input.sort();
-    println!("Wildcard Winnings: {}", input.iter().enumerate().map(|(i, (_, b))| b*(i as u64+1)).sum::<u64>());
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        Ok(winnings(input.iter().sorted()).to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        let wild: Vec<_> = input.iter().map(|(h, b)| (Hand::create(jacks_wild(&h.str)), *b)).collect();
+        Ok(winnings(wild.iter().sorted()).to_string())
+    }
+}
 
-    Ok(())
+fn winnings<'a>(sorted_hands: impl Iterator<Item=&'a (Hand, u64)>) -> u64 {
+    sorted_hands.enumerate().map(|(i, (_, b))| b*(i as u64+1)).sum()
 }
 
 fn jacks_wild(hands: &str) -> String {
@@ -57,7 +75,7 @@ enum Type {
 }
 
 #[derive(Debug)]
-struct Hand {
+pub struct Hand {
     str: String,
     _type: Cell<Option<Type>>,
 }
@@ -3,20 +3,36 @@ use anyhow::*;
 use itertools::Itertools;
 use lazy_regex::regex_captures;
 
+use advent_2023::solution::{run, Solution};
+
 fn main() -> Result<()> {
-    let (dirs, paths) = parse_input(include_str!("input.txt"))?;
-    let (dest, dist) = steps_to(&dirs, &paths, "AAA");
-    println!("Steps to {}: {}", dest, dist);
-
-    let mut steps = Vec::new();
-    for source in all_starts(&paths) {
-        let (_dest, dist) = steps_to(&dirs, &paths, source);
-        //println!("\tSteps to {}: {}", _dest, dist);
-        steps.push(dist);
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 8;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = (String, HashMap<String, (String, String)>);
+
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1((dirs, paths): &Self::Input) -> Result<String> {
+        let (_dest, dist) = steps_to(dirs, paths, "AAA");
+        Ok(dist.to_string())
     }
-    println!("Steps to all ..Z's: {}", fold_lcm(&steps));
 
-    Ok(())
+    fn part2((dirs, paths): &Self::Input) -> Result<String> {
+        let mut steps = Vec::new();
+        for source in all_starts(paths) {
+            let (_dest, dist) = steps_to(dirs, paths, source);
+            //println!("\tSteps to {}: {}", _dest, dist);
+            steps.push(dist);
+        }
+        Ok(fold_lcm(&steps).to_string())
+    }
 }
 
 fn steps_to<'a>(dirs: &str, paths: &'a HashMap<String, (String, String)>, start: &'a str) -> (&'a str, u64) {
@@ -68,6 +84,7 @@ fn steps_to_all(dirs: &str, paths: &HashMap<String, (String, String)>) -> u64 {
     unreachable!()
 }
 
+#[allow(clippy::type_complexity)]
 fn parse_input(input: &str) -> Result<(String, HashMap<String, (String, String)>)> {
     let (dirs, paths) = input.split("\n\n").collect_tuple().context("Invalid")?;
     let paths = paths.lines().map(|l| {
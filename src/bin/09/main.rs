@@ -1,13 +1,28 @@
 use anyhow::*;
 
+use advent_2023::solution::{run, Solution};
+
 fn main() -> Result<()> {
-    let input = parse_input(include_str!("input.txt"))?;
-    let sums = input.iter()
-        .map(|d| extrapolate(d))
-        .fold((0, 0), |b, v| (b.0+v.0, b.1+v.1));
-    println!("Sum of next values:  {}", sums.1);
-    println!("Sum of prior values: {}", sums.0);
-    Ok(())
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 9;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        Ok(input.iter().map(|d| extrapolate(d).1).sum::<i32>().to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        Ok(input.iter().map(|d| extrapolate(d).0).sum::<i32>().to_string())
+    }
 }
 
 fn extrapolate(values: &[i32]) -> (i32, i32) {
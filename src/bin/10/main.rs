@@ -1,24 +1,39 @@
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashSet, VecDeque};
 use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
 use std::time::Instant;
 use anyhow::*;
 
 use advent_2023::collect::MoreItertools;
-use advent_2023::euclid::{Bounds, Point, point, Vector, vector};
+use advent_2023::euclid::{Axes, Direction, Grid, Point, Polygon};
 use advent_2023::pathfinding::{Edge, Graph};
+use advent_2023::solution::{run, Solution};
 use advent_2023::terminal::{Color, Terminal, TerminalDisplay, TerminalString};
 
 fn main() -> Result<()> {
     let _drop = Terminal::init();
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 10;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Map;
 
-    let map: Map = include_str!("example4.txt").parse()?;
-    let members = map.loop_members();
-    println!("Members: {}", members.len() / 2);
-    println!("Interior: {:?}", map.interior(&members).len());
-    Terminal::interactive_color_display(&map, Instant::now());
+    fn parse(input: &str) -> Result<Self::Input> { input.parse() }
 
-    Ok(())
+    fn part1(map: &Self::Input) -> Result<String> {
+        Ok((map.loop_members().len() / 2).to_string())
+    }
+
+    fn part2(map: &Self::Input) -> Result<String> {
+        let interior = Polygon::new(map.loop_path()).interior_points().context("The loop has no area")?;
+        Terminal::interactive_color_display(map, Instant::now());
+        Ok(interior.to_string())
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -33,15 +48,18 @@ enum Pipe {
 }
 
 impl Pipe {
-    fn directions(&self) -> [Vector; 2] {
+    const CONNECTORS: [Pipe; 6] = [
+        Pipe::Vertical, Pipe::Horizontal, Pipe::NorthEast, Pipe::NorthWest, Pipe::SouthWest, Pipe::SouthEast];
+
+    fn directions(&self) -> [Direction; 2] {
         use Pipe::*;
         match self {
-            Vertical => [vector(0, -1), vector(0, 1)],
-            Horizontal => [vector(-1, 0), vector(1, 0)],
-            NorthEast=> [vector(0, -1), vector(1, 0)],
-            NorthWest=> [vector(0, -1), vector(-1, 0)],
-            SouthWest=> [vector(0, 1), vector(-1, 0)],
-            SouthEast=> [vector(0, 1), vector(1, 0)],
+            Vertical => [Direction::North, Direction::South],
+            Horizontal => [Direction::West, Direction::East],
+            NorthEast=> [Direction::North, Direction::East],
+            NorthWest=> [Direction::North, Direction::West],
+            SouthWest=> [Direction::South, Direction::West],
+            SouthEast=> [Direction::South, Direction::East],
             Start=> panic!(),
         }
     }
@@ -93,38 +111,33 @@ impl FromStr for Pipe {
 }
 
 #[derive(Debug)]
-struct Map {
-    pipes: HashMap<Point, Pipe>,
-    bounds: Bounds,
+pub struct Map {
+    pipes: Grid<Option<Pipe>>,
     start: Point,
 }
 
 impl Map {
+    // The pipe at pos, or None if the tile is empty or out of bounds
+    fn pipe(&self, pos: Point) -> Option<Pipe> {
+        self.pipes.get(pos).copied().flatten()
+    }
+
     fn start_type(&self) -> Pipe {
-        debug_assert_eq!(self.pipes.get(&self.start), Some(&Pipe::Start));
-        let mut neighbors = Vec::new();
-        for dir in Vector::CARDINAL.iter() {
-            let neighbor = self.start + dir;
-            if let Some(rev_dirs) = self.pipes.get(&neighbor).map(|p| p.directions()) {
-                if rev_dirs.iter().any(|d| neighbor + d == self.start) {
-                    neighbors.push(*dir);
-                }
-            }
-        }
-        neighbors.sort_by_key(|v| v.x); // Incorrect but good enough for this task
-        assert_eq!(neighbors.len(), 2);
-        match neighbors[..] {
-            [Vector{x:-1, y:0}, Vector{x:0, y:-1}] => { Pipe::NorthWest },
-            [Vector{x:-1, y:0}, Vector{x:0, y:1}] => { Pipe::SouthWest },
-            [Vector{x:0, y:1}, Vector{x:1, y:0}] => { Pipe::SouthEast },
-            _ => unimplemented!("{:?}", neighbors),
-        }
+        debug_assert_eq!(self.pipe(self.start), Some(Pipe::Start));
+        let connected: Vec<_> = Direction::CARDINAL.into_iter()
+            .filter(|dir| self.pipe(self.start + dir.vector())
+                .is_some_and(|p| p.directions().contains(&dir.opposite())))
+            .collect();
+        assert_eq!(connected.len(), 2, "{:?}", connected);
+        Pipe::CONNECTORS.into_iter()
+            .find(|p| p.directions().iter().all(|d| connected.contains(d)))
+            .expect("Some pipe connects any two directions")
     }
 
     #[cfg(test)]
     fn loop_distance(&self) -> usize {
         let bfs_routes = self.bfs_all(&self.start);
-        bfs_routes.iter().map(|(_dest, route)| route.len()).max().expect("Non-empty") - 1
+        bfs_routes.values().map(|route| route.len()).max().expect("Non-empty") - 1
     }
 
     fn loop_members(&self) -> HashSet<Point> {
@@ -143,15 +156,31 @@ impl Map {
         seen
     }
 
+    // The loop's tiles in order, beginning at the start tile
+    fn loop_path(&self) -> Vec<Point> {
+        let mut path = vec![self.start];
+        let mut prev = self.start;
+        let mut current = *self.neighbors(&self.start)[0].dest();
+        while current != self.start {
+            path.push(current);
+            let next = *self.neighbors(&current).iter().map(|e| e.dest()).find(|&d| *d != prev).expect("Loop");
+            prev = current;
+            current = next;
+        }
+        path
+    }
+
+    // Scans each row for tiles inside the loop. Part 2 only needs the count, which the loop's
+    // Polygon computes directly, but the display needs to know which tiles are inside.
     fn interior(&self, loop_members: &HashSet<Point>) -> HashSet<Point> {
         let mut interior = HashSet::new();
-        for row in self.bounds.iter_rows() {
+        for row in self.pipes.bounds().iter_rows() {
             let mut crossed = 0;
             let mut last_bend = None;
             for pos in row {
                 if loop_members.contains(&pos) {
                     use Pipe::*;
-                    let mut pipe = *self.pipes.get(&pos).expect("Present");
+                    let mut pipe = self.pipe(pos).expect("Present");
                     if pipe == Pipe::Start {
                         pipe = self.start_type();
                     }
@@ -185,27 +214,17 @@ impl Graph for Map {
     type Node = Point;
 
     fn neighbors(&self, source: &Self::Node) -> Vec<Edge<Self::Node>> {
-        let mut cur_pipe = *self.pipes.get(source).expect("Missing");
+        let mut cur_pipe = self.pipe(*source).expect("Missing");
         if cur_pipe == Pipe::Start {
             cur_pipe = self.start_type();
         }
-        cur_pipe.directions().iter().map(|dest| Edge::new(1, *source, source+dest)).collect()
+        cur_pipe.directions().iter().map(|dir| Edge::new(1, *source, source + dir.vector())).collect()
     }
 }
 
 impl Display for Map {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut out = String::new();
-        for row in self.bounds.iter_rows() {
-            for pos in row {
-                match self.pipes.get(&pos) {
-                    Some(pipe) => out.push_str(&pipe.to_string()),
-                    None => out.push('.'),
-                }
-            }
-            out.push('\n');
-        }
-        assert_eq!(out.pop(), Some('\n')); // removed trailing newline
+        let out = Axes::Screen.render(self.pipes.bounds(), |pos| self.pipe(pos).map_or('.', |p| p.to_char()));
         write!(f, "{}", out)
     }
 }
@@ -217,9 +236,10 @@ impl TerminalDisplay for Map {
         let interior = self.interior(&members);
 
         let mut pixels = Vec::new();
-        for row in self.bounds.iter_rows().take(height_hint) {
+        let bounds = self.pipes.bounds();
+        for row in bounds.iter_rows().take(height_hint) {
             for pos in row.take(width_hint) {
-                let c = self.pipes.get(&pos).map_or('.', |p| p.to_char());
+                let c = self.pipe(pos).map_or('.', |p| p.to_char());
                 let color = if self.start == pos {
                     Some(Color::YELLOW)
                 } else if members.contains(&pos) {
@@ -234,7 +254,7 @@ impl TerminalDisplay for Map {
             }
         }
 
-        TerminalString{ pixels, width: (self.bounds.max.x+1-self.bounds.min.x) as usize }
+        TerminalString{ pixels, width: (bounds.max.x+1-bounds.min.x) as usize }
     }
 }
 
@@ -242,24 +262,11 @@ impl FromStr for Map {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let mut pipes = HashMap::new();
-        let mut start = None;
-        for (y, l) in s.lines().enumerate() {
-            for (x, c) in l.chars().enumerate() {
-                if c != '.' {
-                    let pos = point(x as i32, y as i32);
-                    let pipe = c.try_into()?;
-                    pipes.insert(pos, pipe);
-                    if let Pipe::Start = pipe {
-                        assert!(start.is_none());
-                        start = Some(pos);
-                    }
-                }
-            }
-        }
-        let bounds = Bounds::from_points(pipes.keys()).context("Non-empty")?;
-        let start = start.context("Start position not found")?;
-        Ok(Map{ pipes, bounds, start })
+        let pipes = Grid::parse(s, |c| if c == '.' { Ok(None) } else { Ok(Some(c.try_into()?)) })?;
+        let starts: Vec<_> = pipes.iter().filter(|(_, p)| **p == Some(Pipe::Start)).map(|(pos, _)| pos).collect();
+        ensure!(starts.len() <= 1, "Multiple start positions: {:?}", starts);
+        let start = *starts.first().context("Start position not found")?;
+        Ok(Map{ pipes, start })
     }
 }
 
@@ -289,6 +296,9 @@ mod tests {
         let map = s.parse::<Map>().unwrap();
         let members = map.loop_members();
         assert_eq!(map.interior(&members).len(), expected);
+        let path = map.loop_path();
+        assert_eq!(path.len(), members.len());
+        assert_eq!(Polygon::new(path).interior_points().unwrap() as usize, expected);
     }}
     interior! {
         e1: (include_str!("example1.txt"), 1),
@@ -4,28 +4,43 @@ use std::ops::RangeInclusive;
 use std::str::FromStr;
 use anyhow::*;
 
-use advent_2023::euclid::{Bounds, Point, point};
+use advent_2023::euclid::{Axes, Bounds, Point, point};
+use advent_2023::solution::{run, Solution};
 
 fn main() -> Result<()> {
-    let input: StarChart = include_str!("input.txt").parse()?;
-    let expanded = StarChart::expand_space(&input, 1);
-    println!("Expanded distances (1): {}", expanded.pair_distances().values().sum::<u64>());
-    let expanded = StarChart::expand_space(&input, 1000000-1);
-    println!("Expanded distances (1000000): {}", expanded.pair_distances().values().sum::<u64>());
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 11;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = StarChart;
+
+    fn parse(input: &str) -> Result<Self::Input> { input.parse() }
 
+    fn part1(input: &Self::Input) -> Result<String> {
+        let expanded = input.expand_space(1);
+        Ok(expanded.pair_distances().values().sum::<u64>().to_string())
+    }
 
-    Ok(())
+    fn part2(input: &Self::Input) -> Result<String> {
+        let expanded = input.expand_space(1000000-1);
+        Ok(expanded.pair_distances().values().sum::<u64>().to_string())
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
-struct StarChart {
+pub struct StarChart {
     // Yes they're galaxies but the term is "start chart" hence "stars"
-    stars: HashSet<Point>,
-    bounds: Bounds,
+    stars: HashSet<Point<i64>>,
+    bounds: Bounds<i64>,
 }
 
 impl StarChart {
-    fn create(stars: HashSet<Point>) -> StarChart {
+    fn create(stars: HashSet<Point<i64>>) -> StarChart {
         let bounds = Bounds::from_points(&stars).expect("Non-empty");
         StarChart{ stars, bounds }
     }
@@ -53,13 +68,13 @@ impl StarChart {
         let mut expanded = HashSet::new();
         for star in &self.stars {
             expanded.insert(point(
-                star.x + (col_offsets[star.x as usize] as i32),
-                star.y + (row_offsets[star.y as usize] as i32)));
+                star.x + (col_offsets[star.x as usize] as i64),
+                star.y + (row_offsets[star.y as usize] as i64)));
         }
         StarChart::create(expanded)
     }
 
-    fn pair_distances(&self) -> BTreeMap<(Point, Point), u64> {
+    fn pair_distances(&self) -> BTreeMap<(Point<i64>, Point<i64>), u64> {
         let mut stars: BTreeSet<_> = self.stars.iter().cloned().collect();
         let mut pairs = BTreeMap::new();
         while let Some(star) = stars.pop_first() {
@@ -73,17 +88,7 @@ impl StarChart {
 
 impl Display for StarChart {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut out = String::new();
-        for row in self.bounds.iter_rows() {
-            for pos in row {
-                match self.stars.contains(&pos) {
-                    true => out.push('#'),
-                    false => out.push('.'),
-                }
-            }
-            out.push('\n');
-        }
-        assert_eq!(out.pop(), Some('\n')); // removed trailing newline
+        let out = Axes::Screen.render(self.bounds, |pos| if self.stars.contains(&pos) { '#' } else { '.' });
         write!(f, "{}", out)
     }
 }
@@ -96,7 +101,7 @@ impl FromStr for StarChart {
         for (y, l) in s.lines().enumerate() {
             for (x, c) in l.chars().enumerate() {
                 if c == '#' {
-                    let pos = point(x as i32, y as i32);
+                    let pos = point(x as i64, y as i64);
                     stars.insert(pos);
                 }
             }
@@ -4,27 +4,49 @@ use anyhow::*;
 use itertools::Itertools;
 use regex::Regex;
 use advent_2023::elapsed;
+use advent_2023::solution::{run, Alternative, Part, Solution};
 
 fn main() -> Result<()> {
-    let input = parse_input(include_str!("input.txt"))?;
-    if cfg!(feature="timing") {
-        elapsed!("Construct valid", input.iter().map(|r| r.construct_valid_rows().len()).sum::<usize>());
-        elapsed!("Count valid", input.iter().map(|r| r.count_valid_rows()).sum::<u64>());
-    }
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 12;
+    const INPUT: &'static str = include_str!("input.txt");
 
-    println!("Possible arrangements: {}", elapsed!("Count cached", count_valid_rows_cached(&input).iter().sum::<u64>()));
+    // The folded and unfolded records
+    type Input = (Vec<Record>, Vec<Record>);
 
-    let input = parse_unfolded_input(include_str!("input.txt"))?;
-    if cfg!(feature="timing") {
-        let partial = &input[0..3];
-        elapsed!("Construct valid unfolded (0..3)", partial.iter().map(|r| r.construct_valid_rows().len()).sum::<usize>());
-        elapsed!("Count valid unfolded (0..3)", partial.iter().map(|r| r.count_valid_rows()).sum::<u64>());
-        elapsed!("Count cached unfolded (0..3)", count_valid_rows_cached(partial).iter().sum::<u64>());
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok((parse_input(input)?, parse_unfolded_input(input)?))
     }
 
-    println!("Possible unfolded arrangements: {}", elapsed!("Count cached unfolded", count_valid_rows_cached(&input).iter().sum::<u64>()));
+    fn part1((input, _): &Self::Input) -> Result<String> {
+        Ok(elapsed!("Count cached", count_valid_rows_cached(input).iter().sum::<u64>()).to_string())
+    }
+
+    fn part2((_, input): &Self::Input) -> Result<String> {
+        Ok(elapsed!("Count cached unfolded", count_valid_rows_cached(input).iter().sum::<u64>()).to_string())
+    }
 
-    Ok(())
+    // The uncached approaches are far too slow for the unfolded records, so for part 2 they're only
+    // benchmarked on the first few records, alongside the primary approach on those same records.
+    fn alternatives() -> Vec<Alternative<Self::Input>> {
+        vec![
+            Alternative{ part: Part::One, name: "Construct valid", solve: |(input, _)|
+                Ok(input.iter().map(|r| r.construct_valid_rows().len()).sum::<usize>().to_string()) },
+            Alternative{ part: Part::One, name: "Count valid", solve: |(input, _)|
+                Ok(input.iter().map(|r| r.count_valid_rows()).sum::<u64>().to_string()) },
+            Alternative{ part: Part::Two, name: "(primary 0..3)", solve: |(_, input)|
+                Ok(count_valid_rows_cached(&input[0..3]).iter().sum::<u64>().to_string()) },
+            Alternative{ part: Part::Two, name: "Construct valid (0..3)", solve: |(_, input)|
+                Ok(input[0..3].iter().map(|r| r.construct_valid_rows().len()).sum::<usize>().to_string()) },
+            Alternative{ part: Part::Two, name: "Count valid (0..3)", solve: |(_, input)|
+                Ok(input[0..3].iter().map(|r| r.count_valid_rows()).sum::<u64>().to_string()) },
+        ]
+    }
 }
 
 fn create_regex(damaged: &[usize]) -> Regex {
@@ -88,12 +110,13 @@ fn valid_rows<'a>(row: &'a str, damaged: &'a [usize], cache: &mut HashMap<(&'a s
 }
 
 #[derive(Debug)]
-struct Record {
+pub struct Record {
     row: String,
     damaged: Vec<usize>,
 }
 
 impl Record {
+    #[allow(unexpected_cfgs)]
     fn create(row: String, damaged: Vec<usize>) -> Result<Record> {
         if cfg!(debug) {
             let validator = create_regex(&damaged);
@@ -150,6 +173,7 @@ impl FromStr for Record {
     }
 }
 
+#[allow(clippy::manual_repeat_n)]
 fn unfold_line(line: &str) -> Result<String> {
     let (left, right) = line.split(' ').collect_tuple().context("Invalid")?;
     let left = std::iter::repeat(left).take(5).join("?");
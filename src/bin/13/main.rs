@@ -1,19 +1,30 @@
-use std::collections::HashSet;
 use std::str::FromStr;
 use anyhow::*;
 
-use advent_2023::elapsed;
-use advent_2023::euclid::{Bounds, Point, point};
+use advent_2023::euclid::{Grid, point, Transform};
+use advent_2023::solution::{run, Solution};
 
 fn main() -> Result<()> {
-    let input = parse_input(include_str!("input.txt"))?;
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 13;
+    const INPUT: &'static str = include_str!("input.txt");
 
-    let sum = elapsed!("Part 1", score(&input, 0));
-    println!("Mirror score: {}", sum);
-    let sum = elapsed!("Part 2", score(&input, 1));
-    println!("Smudged score: {}", sum);
+    type Input = Vec<Landscape>;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        Ok(score(input, 0).to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        Ok(score(input, 1).to_string())
+    }
 }
 
 fn score(landscapes: &[Landscape], expected_errors: u32) -> i32 {
@@ -30,83 +41,64 @@ fn score(landscapes: &[Landscape], expected_errors: u32) -> i32 {
 }
 
 #[derive(Debug)]
-struct Landscape {
-    rocks: HashSet<Point>,
-    bounds: Bounds,
+pub struct Landscape {
+    rocks: Grid<bool>,
+    // Rows become columns, so only one orientation of the reflection search is needed
+    transposed: Grid<bool>,
 }
 
 impl Landscape {
     fn horizontal_reflection(&self, expected_errors: u32) -> Option<i32> {
-        for edge in self.bounds.min.x+1..=self.bounds.max.x {
-            let mut errors = 0;
-            for y in self.bounds.min.y..=self.bounds.max.y {
-                errors += self.mirrored_row(edge, y, expected_errors - errors);
-                if errors > expected_errors { break; }
-            }
-            if errors == expected_errors { return Some(edge); }
-        }
-        None
+        reflection(&self.rocks, expected_errors)
     }
 
-    fn mirrored_row(&self, edge: i32, y: i32, max_errors: u32) -> u32 {
-        let dist = std::cmp::min(edge-self.bounds.min.x, self.bounds.max.x+1-edge);
-        assert_ne!(dist, 0);
-        let mut errors = 0;
-        for x_offset in 0..dist {
-            let left = point(edge-1-x_offset, y);
-            let right = point(edge+x_offset, y);
-            if self.rocks.contains(&left) != self.rocks.contains(&right) {
-                errors += 1;
-                if errors > max_errors { break; }
-            }
-        }
-        errors
+    fn vertical_reflection(&self, expected_errors: u32) -> Option<i32> {
+        reflection(&self.transposed, expected_errors)
     }
+}
 
-    fn vertical_reflection(&self, expected_errors: u32) -> Option<i32> {
-        for edge in self.bounds.min.y+1..=self.bounds.max.y {
-            let mut errors = 0;
-            for x in self.bounds.min.x..=self.bounds.max.x {
-                errors += self.mirrored_column(edge, x, expected_errors - errors);
-                if errors > expected_errors { break; }
-            }
-            if errors == expected_errors { return Some(edge); }
+// Finds the x coordinate immediately right of a vertical line that reflects the grid with exactly
+// the expected number of errors.
+fn reflection(rocks: &Grid<bool>, expected_errors: u32) -> Option<i32> {
+    let bounds = rocks.bounds();
+    for edge in bounds.min.x+1..=bounds.max.x {
+        let mut errors = 0;
+        for y in bounds.min.y..=bounds.max.y {
+            errors += mirrored_row(rocks, edge, y, expected_errors - errors);
+            if errors > expected_errors { break; }
         }
-        None
+        if errors == expected_errors { return Some(edge); }
     }
+    None
+}
 
-    fn mirrored_column(&self, edge: i32, x: i32, max_errors: u32) -> u32 {
-        let dist = std::cmp::min(edge-self.bounds.min.y, self.bounds.max.y+1-edge);
-        assert_ne!(dist, 0);
-        let mut errors = 0;
-        for y_offset in 0..dist {
-            let top = point(x, edge-1-y_offset);
-            let bottom = point(x, edge+y_offset);
-            if self.rocks.contains(&top) != self.rocks.contains(&bottom) {
-                errors += 1;
-                if errors > max_errors { break; }
-            }
+fn mirrored_row(rocks: &Grid<bool>, edge: i32, y: i32, max_errors: u32) -> u32 {
+    let bounds = rocks.bounds();
+    let dist = std::cmp::min(edge-bounds.min.x, bounds.max.x+1-edge);
+    assert_ne!(dist, 0);
+    let mut errors = 0;
+    for x_offset in 0..dist {
+        let left = point(edge-1-x_offset, y);
+        let right = point(edge+x_offset, y);
+        if rocks[left] != rocks[right] {
+            errors += 1;
+            if errors > max_errors { break; }
         }
-        errors
     }
+    errors
 }
 
 impl FromStr for Landscape {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let mut rocks = HashSet::new();
-        for (y, l) in s.lines().enumerate() {
-            for (x, c) in l.chars().enumerate() {
-                match c {
-                    '#' => { rocks.insert(point(x as i32, y as i32)); },
-                    '.' => {},
-                    _ => bail!("Invalid"),
-                }
-            }
-        }
-        let bounds = Bounds::from_points(&rocks).context("Empty")?;
-        Ok(Landscape{ rocks, bounds })
+        let rocks = Grid::parse(s, |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => bail!("Invalid"),
+        })?;
+        let transposed = Transform::Transpose.grid(&rocks);
+        Ok(Landscape{ rocks, transposed })
     }
 }
 
@@ -1,72 +1,83 @@
-use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::str::FromStr;
 use anyhow::*;
 
 use advent_2023::elapsed;
-use advent_2023::euclid::{Bounds, Point, point, vector, Vector};
+use advent_2023::euclid::{Direction, Grid, Transform};
+use advent_2023::solution::{run, Solution};
 
 fn main() -> Result<()> {
-    let mut input: Platform = include_str!("input.txt").parse()?;
-    input.tip(vector(0, -1));
-    println!("Initial load: {}", input.north_load());
-    // This re-does one tip((0, -1)) but it's a no-op so it's harmless aside from the CPU time
-    // It so happens that a lookback of 1 is sufficient for the input, but the example requires at
-    // least 3 lookback due to duplicate numbers prior to the cycle start, so use 5 to be safe.
-    println!("Long-term load: {}", elapsed!(input.load_after(5, 1000000000)));
-
-    Ok(())
+    run::<Day>()
 }
 
-#[derive(Debug, Eq, PartialEq)]
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 14;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Platform;
+
+    fn parse(input: &str) -> Result<Self::Input> { input.parse() }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        let mut platform = input.clone();
+        platform.tip(Direction::North);
+        Ok(platform.north_load().to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        let mut platform = input.clone();
+        // It so happens that a lookback of 1 is sufficient for the input, but the example requires at
+        // least 3 lookback due to duplicate numbers prior to the cycle start, so use 5 to be safe.
+        Ok(elapsed!(platform.load_after(5, 1000000000)).to_string())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 enum Rock {
     Round,
     Cube,
 }
 
-#[derive(Debug, Eq, PartialEq)]
-struct Platform {
-    grid: HashMap<Point, Rock>,
-    bounds: Bounds,
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Platform {
+    grid: Grid<Option<Rock>>,
 }
 
 impl Platform {
-    fn tip(&mut self, dir: Vector) {
-        fn iter_order(bounds: Bounds, dir: Vector) -> Box<dyn Iterator<Item=Point>> {
-            // This is a bit gross, could probably be avoided
-            match dir {
-                Vector{x:0, y:-1} => Box::new((bounds.min.y..=bounds.max.y)
-                    .flat_map(move |y| (bounds.min.x..=bounds.max.x).map(move |x| point(x, y)))),
-                Vector{x:0, y:1} => Box::new((bounds.min.y..=bounds.max.y).rev()
-                    .flat_map(move |y| (bounds.min.x..=bounds.max.x).map(move |x| point(x, y)))),
-                Vector{x:-1, y:0} => Box::new((bounds.min.x..=bounds.max.x)
-                    .flat_map(move |x| (bounds.min.y..=bounds.max.y).map(move |y| point(x, y)))),
-                Vector{x:1, y:0} => Box::new((bounds.min.x..=bounds.max.x).rev()
-                    .flat_map(move |x| (bounds.min.y..=bounds.max.y).map(move |y| point(x, y)))),
-                _ => unimplemented!(),
-            }
+    // Tips the platform so that the round rocks roll as far as possible in the given direction.
+    // This is implemented for north, and other directions rotate the platform to face north.
+    fn tip(&mut self, dir: Direction) {
+        assert!(dir.is_cardinal(), "Can't tip towards {}", dir);
+        let rotation = Transform::rotation_between(dir.vector(), Direction::North.vector()).expect("Cardinal");
+        if rotation == Transform::Identity {
+            Platform::tip_north(&mut self.grid);
+            return;
         }
+        let mut grid = rotation.grid(&self.grid);
+        Platform::tip_north(&mut grid);
+        self.grid = rotation.inverse().grid(&grid);
+    }
 
-        for pos in iter_order(self.bounds, dir) {
-            if !matches!(self.grid.get(&pos), Some(Rock::Round)) { continue; }
+    fn tip_north(grid: &mut Grid<Option<Rock>>) {
+        let north = Direction::North.vector();
+        let bounds = grid.bounds();
+        for pos in bounds.iter() {
+            if grid[pos] != Some(Rock::Round) { continue; }
             let mut dest = pos;
-            loop {
-                let next = dest + dir;
-                if !self.bounds.contains(next) || self.grid.contains_key(&next) {
-                    break;
-                }
-                dest = next;
+            while let Some(None) = grid.get(dest + north) {
+                dest += north;
             }
-            let value = self.grid.remove(&pos).expect("Must be present");
-            self.grid.insert(dest, value);
+            let value = grid[pos].take();
+            grid[dest] = value;
         }
     }
 
     fn cycle(&mut self) {
-        self.tip(vector(0, -1));
-        self.tip(vector(-1, 0));
-        self.tip(vector(0, 1));
-        self.tip(vector(1, 0));
+        for dir in [Direction::North, Direction::West, Direction::South, Direction::East] {
+            self.tip(dir);
+        }
     }
 
     fn find_loop(&mut self, lookback: usize) -> (usize, Vec<u64>) {
@@ -89,8 +100,8 @@ impl Platform {
 
     fn north_load(&self) -> u64 {
         self.grid.iter()
-            .filter(|(_, v)| matches!(v, Rock::Round))
-            .map(|(p,_)| (self.bounds.max.y+1-p.y) as u64)
+            .filter(|(_, v)| matches!(v, Some(Rock::Round)))
+            .map(|(p,_)| (self.grid.bounds().max.y+1-p.y) as u64)
             .sum()
     }
 
@@ -104,29 +115,22 @@ impl FromStr for Platform {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let mut grid = HashMap::new();
-        for (y, l) in s.lines().enumerate() {
-            for (x, c) in l.chars().enumerate() {
-                let pos = point(x as i32, y as i32);
-                match c {
-                    'O' => { grid.insert(pos, Rock::Round); },
-                    '#' => { grid.insert(pos, Rock::Cube); },
-                    '.' => {},
-                    _ => bail!("Invalid: {}", c),
-                }
-            }
-        }
-        let bounds = Bounds::from_points(grid.keys()).context("Invalid")?;
-        Ok(Platform{ grid, bounds })
+        let grid = Grid::parse(s, |c| Ok(match c {
+            'O' => Some(Rock::Round),
+            '#' => Some(Rock::Cube),
+            '.' => None,
+            _ => bail!("Invalid: {}", c),
+        }))?;
+        Ok(Platform{ grid })
     }
 }
 
 impl Display for Platform {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let mut out = String::new();
-        for row in self.bounds.iter_rows() {
-            for pos in row {
-                match self.grid.get(&pos) {
+        for row in self.grid.rows() {
+            for rock in row {
+                match rock {
                     Some(Rock::Round) => out.push('O'),
                     Some(Rock::Cube) => out.push('#'),
                     None => out.push('.'),
@@ -149,7 +153,7 @@ mod tests {
     #[test]
     fn tip_north() {
         let mut platform = include_str!("example.txt").parse::<Platform>().unwrap();
-        platform.tip(vector(0, -1));
+        platform.tip(Direction::North);
         assert_eq!(platform, include_str!("example_tipped.txt").parse().unwrap());
     }
 
@@ -3,13 +3,29 @@ use std::str::FromStr;
 use anyhow::*;
 use itertools::Itertools;
 
+use advent_2023::solution::{run, Solution};
+
 fn main() -> Result<()> {
-    let input = parse_input(include_str!("example.txt"))?;
-    println!("HASH Sum: {}", input.iter().map(|s| holiday_ascii_string_helper(s) as u32).sum::<u32>());
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 15;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Vec<String>;
 
-    let input = to_ops(&input)?;
-    println!("Focusing Power: {}", execute(&input));
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        Ok(input.iter().map(|s| holiday_ascii_string_helper(s) as u32).sum::<u32>().to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        Ok(execute(&to_ops(input)?).to_string())
+    }
 }
 
 fn holiday_ascii_string_helper(s: &str) -> u8 {
@@ -93,7 +109,7 @@ fn execute(ops: &[Op]) -> u32 {
 }
 
 fn parse_input(input: &str) -> Result<Vec<String>> {
-    Ok(input.split(',').map(|s| s.into()).collect())
+    Ok(input.trim_end().split(',').map(|s| s.into()).collect())
 }
 
 fn to_ops(input: &[String]) -> Result<Vec<Op>> {
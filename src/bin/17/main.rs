@@ -5,33 +5,58 @@ use std::str::FromStr;
 use anyhow::*;
 use advent_2023::elapsed;
 
-use advent_2023::euclid::{Bounds, Point, point, Vector, vector};
+use advent_2023::euclid::{Direction, Grid, Point, point, Vector};
 use advent_2023::pathfinding::{Edge, Graph};
+use advent_2023::solution::{run, Alternative, Part, Solution};
 
 fn main() -> Result<()> {
-    let map: Map = include_str!("input.txt").parse()?;
-    let crucible = Crucible{ map: &map, straight_travel: 1..=3 };
-    println!("Crucible heat loss: {}", crucible.path().unwrap());
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 17;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input> { input.parse() }
 
-    let ultra = Crucible{ map: &map, straight_travel: 4..=10 };
-    println!("UltraCrucible heat loss: {}", ultra.path().unwrap());
+    fn part1(map: &Self::Input) -> Result<String> {
+        let crucible = Crucible{ map, straight_travel: 1..=3 };
+        Ok(crucible.path(Search::Dijkstras).context("No path found")?.to_string())
+    }
+
+    fn part2(map: &Self::Input) -> Result<String> {
+        let ultra = Crucible{ map, straight_travel: 4..=10 };
+        Ok(ultra.path(Search::Dijkstras).context("No path found")?.to_string())
+    }
 
-    Ok(())
+    // A* would normally be faster (and it is if you start e.g. in the middle of the map), but
+    // because we start in the top-left and end in the bottom-right Dijkstra's covers essentially
+    // the same search space as A* without as much overhead. The map's edge cost cache is shared
+    // by every run, so after the benchmark's warmup both searches are timed against a full cache.
+    fn alternatives() -> Vec<Alternative<Self::Input>> {
+        vec![
+            Alternative{ part: Part::One, name: "A*", solve: |map|
+                Ok(Crucible{ map, straight_travel: 1..=3 }.path(Search::AStar).context("No path found")?.to_string()) },
+            Alternative{ part: Part::Two, name: "A*", solve: |map|
+                Ok(Crucible{ map, straight_travel: 4..=10 }.path(Search::AStar).context("No path found")?.to_string()) },
+        ]
+    }
 }
 
+#[derive(Copy, Clone, Debug)]
+enum Search { Dijkstras, AStar }
+
 #[derive(Debug)]
-struct Map {
-    costs: HashMap<Point, i32>,
-    bounds: Bounds,
+pub struct Map {
+    costs: Grid<i32>,
     cache: RefCell<HashMap<(Vector, Point), i32>>, // (Dir, Dest) -> CostFromEdge
 }
 
 impl Map {
-    fn create(costs: HashMap<Point, i32>) -> Result<Map> {
-        let bounds = Bounds::from_points(costs.keys()).context("Non-empty")?;
-        Ok(Map{ costs, bounds, cache: RefCell::default() })
-    }
-
     // Returns the cost from source to dest, i.e. the sum of the blocks between these points
     // _excluding_ source. Returns None if such a path does not exist.
 
@@ -43,12 +68,12 @@ impl Map {
             point(pos.x - dir.x, pos.y - dir.y)
         }
 
-        fn edge_cost(dir: Vector, dest: Point, costs: &HashMap<Point, i32>, cache: &mut HashMap<(Vector, Point), i32>) -> Option<i32> {
+        fn edge_cost(dir: Vector, dest: Point, costs: &Grid<i32>, cache: &mut HashMap<(Vector, Point), i32>) -> Option<i32> {
             debug_assert_eq!(dir, dir.signum());
             let cached = cache.get(&(dir, dest)).cloned();
             if cached.is_some() { return cached; } // avoiding if let so the RefCell borrow checker is happy
 
-            let cost = *costs.get(&dest)?; // None if dest is invalid
+            let cost = *costs.get(dest)?; // None if dest is invalid
             let prior = vec_sub(dest, dir);
             let prior_cost = edge_cost(dir, prior, costs, cache).unwrap_or(0);
             cache.insert((dir, dest), cost + prior_cost);
@@ -70,19 +95,14 @@ struct Crucible<'a> {
 }
 
 impl<'a> Crucible<'a> {
-    fn path(&self) -> Option<i32> {
-        let start = (self.map.bounds.min, vector(0,0));
-        let target = self.map.bounds.max;
-        let goal = |d: &<Crucible<'a> as Graph>::Node| d.0 == self.map.bounds.max;
-        if cfg!(feature="timing") {
-            self.map.cache.borrow_mut().clear();
-            // A* would normally be faster (and it is if you start e.g. in the middle of the map),
-            // but because we start in the top-left and end in the bottom-right Dijkstra's covers
-            // essentially the same search space as A* without as much overhead.
-            elapsed!("A*", self.a_star(&start, goal, |(pos, _)| (target - *pos).grid_len() as i32));
-            self.map.cache.borrow_mut().clear();
-        }
-        let path = elapsed!("Dijkstra's", self.dijkstras(&start, goal));
+    fn path(&self, search: Search) -> Option<i32> {
+        let start = (self.map.costs.bounds().min, None);
+        let target = self.map.costs.bounds().max;
+        let goal = |d: &<Crucible<'a> as Graph>::Node| d.0 == target;
+        let path = match search {
+            Search::Dijkstras => elapsed!("Dijkstra's", self.dijkstras(&start, goal)),
+            Search::AStar => elapsed!("A*", self.a_star(&start, goal, |(pos, _)| (target - *pos).grid_len())),
+        };
         // println!("Path:");
         // path.as_ref().unwrap().iter().for_each(|e| println!("{:?}", e));
         path.map(|v| v.iter().map(|e| e.weight()).sum::<i32>())
@@ -90,27 +110,24 @@ impl<'a> Crucible<'a> {
 }
 
 impl<'a> Graph for Crucible<'a> {
-    type Node = (Point, Vector);
+    // The crucible's position and the direction it moved to get there, if it has moved
+    type Node = (Point, Option<Direction>);
 
     fn neighbors(&self, source: &Self::Node) -> Vec<Edge<Self::Node>> {
         let (pos, dir) = source;
         let dest_to_edge = |(dest, dir): Self::Node| self.map.path_cost(*pos, dest)
             .map(|c| Edge::new(c, *source, (dest, dir)));
 
-        if *dir == Vector::ZERO {
+        let turns = match dir {
             // Crucible is not moving (i.e it must be at the start); allow it to go in all directions
-            return Vector::CARDINAL.iter()
-                .flat_map(|v| self.straight_travel.clone().map(|n| (pos + (*v * n), *v)))
-                .filter_map(dest_to_edge)
-                .collect();
-        }
-        debug_assert!(Vector::CARDINAL.contains(dir));
-
-        // Having moved straight to get to this location we can only move right or left now (a
-        // different path would have moved straight more/fewer steps). This way the Node doesn't
-        // need to track how many steps forward we've taken since we return all valid straight paths.
-        [dir.left90(), dir.right90()].iter()
-            .flat_map(|v| self.straight_travel.clone().map(|n| (pos + (*v * n), *v)))
+            None => Direction::CARDINAL.to_vec(),
+            // Having moved straight to get to this location we can only move right or left now (a
+            // different path would have moved straight more/fewer steps). This way the Node doesn't
+            // need to track how many steps forward we've taken since we return all valid straight paths.
+            Some(dir) => vec![dir.left90(), dir.right90()],
+        };
+        turns.into_iter()
+            .flat_map(|d| self.straight_travel.clone().map(move |n| (pos + (d.vector() * n), Some(d))))
             .filter_map(dest_to_edge)
             .collect()
     }
@@ -120,15 +137,8 @@ impl FromStr for Map {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let mut costs = HashMap::new();
-        for (y, l) in s.lines().enumerate() {
-            for (x, c) in l.chars().enumerate() {
-                let pos = point(x as i32, y as i32);
-                let cost = c.to_digit(10).context("Invalid")? as i32;
-                costs.insert(pos, cost);
-            }
-        }
-        Map::create(costs)
+        let costs = Grid::parse(s, |c| Ok(c.to_digit(10).context("Invalid")? as i32))?;
+        Ok(Map{ costs, cache: RefCell::default() })
     }
 }
 
@@ -142,7 +152,7 @@ mod tests {
     parameterized_test::create!{ part1, (input, loss), {
         let map: Map = input.parse().unwrap();
         let crucible = Crucible{ map: &map, straight_travel: 1..=3 };
-        assert_eq!(crucible.path().unwrap(), loss);
+        assert_eq!(crucible.path(Search::Dijkstras).unwrap(), loss);
     }}
     part1! {
         e1: (include_str!("example1.txt"), 102),
@@ -154,7 +164,7 @@ mod tests {
     parameterized_test::create!{ part2, (input, loss), {
         let map: Map = input.parse().unwrap();
         let ultra = Crucible{ map: &map, straight_travel: 4..=10 };
-        assert_eq!(ultra.path().unwrap(), loss);
+        assert_eq!(ultra.path(Search::Dijkstras).unwrap(), loss);
     }}
     part2! {
         e1: (include_str!("example1.txt"), 94),
@@ -162,4 +172,15 @@ mod tests {
         // usually we don't test the input, but the coverage seems worthwhile here and it's pretty fast
         i: (include_str!("input.txt"), 1219),
     }
+
+    parameterized_test::create!{ a_star, (input, straight_travel), {
+        let map: Map = input.parse().unwrap();
+        let crucible = Crucible{ map: &map, straight_travel };
+        assert_eq!(crucible.path(Search::AStar), crucible.path(Search::Dijkstras));
+    }}
+    a_star! {
+        e1: (include_str!("example1.txt"), 1..=3),
+        e1_ultra: (include_str!("example1.txt"), 4..=10),
+        e2_ultra: (include_str!("example2.txt"), 4..=10),
+    }
 }
@@ -4,23 +4,41 @@ use anyhow::*;
 use lazy_regex::regex_captures;
 use advent_2023::elapsed;
 
-use advent_2023::euclid::{Bounds, bounds, Point, Vector, vector};
+use advent_2023::euclid::{Bounds, bounds, Direction, Point, Polygon, segment, Vector, vector};
+use advent_2023::solution::{run, Alternative, Part, Solution};
 
 fn main() -> Result<()> {
-    let input = parse_input(include_str!("input.txt"))?;
-    if cfg!(feature="timing") {
-        let mut lagoon = elapsed!("Border", Lagoon::create(&input));
-        println!("Perimeter: {}", lagoon.border.len());
-        println!("Area: {}", elapsed!("Interior", lagoon.trench_interior()) + lagoon.border.len() as i32);
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 18;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Vec<Trench>;
+
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        Ok(elapsed!("Polygon", lagoon_size(input.iter().map(|i| &i.path)))?.to_string())
+    }
+
+    fn part2(input: &Self::Input) -> Result<String> {
+        Ok(elapsed!("Color Polygon", lagoon_size(input.iter().map(|i| &i.color_path)))?.to_string())
     }
-    println!("Initial Area: {}", elapsed!("Polygon", Polygon::create(input.iter().map(|i| &i.path)).area()));
-    println!("Corrected Area: {}", elapsed!("Color Polygon", Polygon::create(input.iter().map(|i| &i.color_path)).area()));
 
-    Ok(())
+    fn alternatives() -> Vec<Alternative<Self::Input>> {
+        vec![Alternative{ part: Part::One, name: "Flood fill", solve: |input| {
+            let mut lagoon = elapsed!("Border", Lagoon::create(input));
+            Ok((elapsed!("Interior", lagoon.trench_interior()) + lagoon.border.len() as i32).to_string())
+        }}]
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
-struct Trench {
+pub struct Trench {
     path: Vector,
     color_path: Vector,
 }
@@ -31,21 +49,10 @@ impl FromStr for Trench {
     fn from_str(s: &str) -> Result<Self> {
         let (_, dir, dist, color_dist, color_dir) =
             regex_captures!(r"(.) (\d+) \(#(.{5})(.)\)", s).with_context(|| format!("Invalid: {}", s))?;
-        let path = match dir {
-            "U" => vector(0, -1),
-            "D" => vector(0, 1),
-            "L" => vector(-1, 0),
-            "R" => vector(1, 0),
-            _ => bail!("Invalid"),
-        } * dist.parse::<i32>()?;
-
-        let color_path = match color_dir {
-            "0" => vector(1, 0),
-            "1" => vector(0, 1),
-            "2" => vector(-1, 0),
-            "3" => vector(0, -1),
-            _ => bail!("Invalid"),
-        } * i32::from_str_radix(color_dist, 16)?;
+        let path = dir.parse::<Direction>()?.vector() * dist.parse::<i32>()?;
+
+        let color_dir = Direction::from_digit(color_dir.parse()?).with_context(|| format!("Invalid direction: {}", color_dir))?;
+        let color_path = color_dir.vector() * i32::from_str_radix(color_dist, 16)?;
 
         Ok(Trench{ path, color_path })
     }
@@ -64,8 +71,7 @@ impl Lagoon {
         let mut pos = Point::ORIGIN;
         for trench in instructions {
             let dest = pos + trench.path;
-            let path = Bounds::from_points(&[pos, dest]).expect("Non-empty");
-            border.extend(path.iter());
+            border.extend(segment(pos, dest).points());
             pos = dest;
         }
         let bounds = Bounds::from_points(&border).expect("Non-empty");
@@ -89,46 +95,11 @@ impl Lagoon {
     }
 }
 
-struct Polygon {
-    edges: Vec<(Point, Point)>,
-    perimeter: i64,
-}
-
-impl Polygon {
-    fn create<'a>(paths: impl IntoIterator<Item = &'a Vector>) -> Polygon {
-        let mut edges = Vec::new();
-        let mut perimeter = 0;
-        let mut pos = Point::ORIGIN;
-        for path in paths.into_iter() {
-            perimeter += path.grid_len() as i64;
-            let dest = pos + path;
-            edges.push((pos, dest));
-            pos = dest;
-        }
-        debug_assert_eq!(edges.last().expect("Non-empty").1, Point::ORIGIN);
-        Polygon{ edges, perimeter }
-    }
-
-    // https://en.wikipedia.org/wiki/Shoelace_formula#Trapezoid_formula
-    fn shoelace_area(&self) -> i64 {
-        self.edges.iter()
-            .map(|e| (e.0.y + e.1.y + 1) as i64 * (e.0.x - e.1.x) as i64)
-            .sum::<i64>().abs() / 2
-    }
-
-    fn area(&self) -> i64 {
-        // Intuitively, we need to add ~half the perimeter to our area to account for the inclusive
-        // nature of our points. For example a box out to (4, 4) has an area of 25, not 16 - adding
-        // ~half the 4x4 perimeter (4 + 4 for the edges + 1 for the corner) gets the correct area.
-        // It's less-obvious that this is correct in general (e.g. for shapes with overlapping
-        // perimeters) but it at least works for the given input.
-        //
-        // That said, I'm still not sold that this would work for any legal input; trenches that run
-        // parallel or crisscross repeatedly might mess things up. Notably, this approach aligns
-        // with https://en.wikipedia.org/wiki/Pick%27s_theorem but supposedly additional information
-        // such as winding numbers are needed for polygons that can cross themselves.
-        self.shoelace_area() + self.perimeter/2 + 1
-    }
+// The lagoon is the trench's loop plus everything it encloses. Color paths are long enough that
+// the area overflows an i32.
+fn lagoon_size<'a>(paths: impl IntoIterator<Item = &'a Vector>) -> Result<i64> {
+    let moves = paths.into_iter().map(|p| p.cast().expect("Widening"));
+    Polygon::<i64>::from_moves(Point::ORIGIN, moves).lattice_points().context("The trench has no area")
 }
 
 fn parse_input(input: &str) -> Result<Vec<Trench>> {
@@ -174,16 +145,17 @@ mod tests {
     #[test]
     fn polygon() {
         let example = parse_input(include_str!("example.txt")).unwrap();
-        let polygon = Polygon::create(example.iter().map(|i| &i.path));
-        assert_eq!(polygon.perimeter, 38);
-        assert_eq!(polygon.area(), 62);
+        let polygon = Polygon::from_moves(Point::ORIGIN, example.iter().map(|i| i.path));
+        assert_eq!(polygon.boundary_points(), 38);
+        assert_eq!(polygon.lattice_points(), Some(62));
+        assert_eq!(lagoon_size(example.iter().map(|i| &i.path)).unwrap(), 62);
     }
 
     #[test]
     fn polygon_color() {
         let example = parse_input(include_str!("example.txt")).unwrap();
-        let polygon = Polygon::create(example.iter().map(|i| &i.color_path));
-        assert_eq!(polygon.perimeter, 6405262); // Not specified
-        assert_eq!(polygon.area(), 952408144115);
+        let polygon = Polygon::<i64>::from_moves(Point::ORIGIN, example.iter().map(|i| i.color_path.cast().unwrap()));
+        assert_eq!(polygon.boundary_points(), 6405262); // Not specified
+        assert_eq!(polygon.lattice_points(), Some(952408144115));
     }
 }
@@ -8,16 +8,31 @@ use lazy_regex::regex_captures;
 use advent_2023::collect;
 
 use advent_2023::collect::{MoreIntoIterator, Range};
+use advent_2023::solution::{run, Solution};
 
 const FULL_RANGE: Range = Range::create(1, 4001);
 
 fn main() -> Result<()> {
-    let (workflows, parts) = parse_input(include_str!("input.txt"))?;
-    let score =  parts.iter().filter(|p| workflows.validate(p)).map(|p| p.score()).sum::<i32>();
-    println!("Valid Parts Score: {}", score);
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 19;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = (WorkflowTable, Vec<Part>);
 
-    println!("Count Valid Parts: {}", workflows.count_valid());
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1((workflows, parts): &Self::Input) -> Result<String> {
+        Ok(parts.iter().filter(|p| workflows.validate(p)).map(|p| p.score()).sum::<i32>().to_string())
+    }
+
+    fn part2((workflows, _): &Self::Input) -> Result<String> {
+        Ok(workflows.count_valid().to_string())
+    }
 }
 
 struct Test {
@@ -134,7 +149,7 @@ impl FromStr for Workflow {
     }
 }
 
-struct WorkflowTable {
+pub struct WorkflowTable {
     workflows: HashMap<String, Workflow>,
 }
 
@@ -214,7 +229,7 @@ impl PartRange {
 }
 
 #[derive(Debug)]
-struct Part {
+pub struct Part {
     x: i32,
     m: i32,
     a: i32,
@@ -5,28 +5,45 @@ use std::str::FromStr;
 use anyhow::*;
 use lazy_regex::regex_captures;
 
+use advent_2023::solution::{run, Solution};
+
 fn main() -> Result<()> {
-    let mut input: Configuration = include_str!("input.txt").parse()?;
+    run::<Day>()
+}
 
-    for _ in 0..1000 {
-        input.press_button()?;
-    }
-    let low = input.counts[&Pulse::Low];
-    let high = input.counts[&Pulse::High];
-    println!("Pulses (low:{} high:{}): {}", low, high, low * high);
-
-    // Nice visualization of the input:
-    // https://old.reddit.com/r/adventofcode/comments/18mypla/2023_day_20_input_data_plot/
-    // There's not really much to be done beyond assuming there are four cycles and waiting for
-    // them to complete. We could traverse from rx to find the cycling nodes, but we'd still have to
-    // assume it's the grandparent nodes that need to be watched, and it doesn't feel particularly
-    // valuable to treat that as variable given that we're assuming the graph is so structured.
-    while input.conjunction_cycles().iter().filter(|&&c| c > 1).count() < 4 {
-        input.press_button()?;
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 20;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Configuration;
+
+    fn parse(input: &str) -> Result<Self::Input> { input.parse() }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        let mut config = input.clone();
+        for _ in 0..1000 {
+            config.press_button()?;
+        }
+        let low = config.counts[&Pulse::Low];
+        let high = config.counts[&Pulse::High];
+        Ok((low * high).to_string())
     }
-    println!("Cycle Length: {}", fold_lcm(input.conjunction_cycles()));
 
-    Ok(())
+    fn part2(input: &Self::Input) -> Result<String> {
+        let mut config = input.clone();
+        // Nice visualization of the input:
+        // https://old.reddit.com/r/adventofcode/comments/18mypla/2023_day_20_input_data_plot/
+        // There's not really much to be done beyond assuming there are four cycles and waiting for
+        // them to complete. We could traverse from rx to find the cycling nodes, but we'd still have to
+        // assume it's the grandparent nodes that need to be watched, and it doesn't feel particularly
+        // valuable to treat that as variable given that we're assuming the graph is so structured.
+        while config.conjunction_cycles().iter().filter(|&&c| c > 1).count() < 4 {
+            config.press_button()?;
+        }
+        Ok(fold_lcm(config.conjunction_cycles()).to_string())
+    }
 }
 
 // Borrowed from Day 8, opting not to generalize atm
@@ -39,7 +56,7 @@ enum Pulse {
     Low, High,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Module {
     FlipFlop(bool),
     Conjunction(BTreeMap<Rc<str>, Pulse>, Option<u64>),
@@ -91,8 +108,8 @@ impl Module {
     }
 }
 
-#[derive(Debug)]
-struct Configuration {
+#[derive(Debug, Clone)]
+pub struct Configuration {
     presses: u64,
     button: Rc<str>,
     broadcaster: Rc<str>,
@@ -275,7 +292,7 @@ mod tests {
         for _ in 0..1000 {
             config.press_button().unwrap();
         }
-        assert_eq!(config.counts, [(Pulse::Low, low), (Pulse::High, high)].into_iter().collect());
+        assert_eq!(config.counts, HashMap::from([(Pulse::Low, low), (Pulse::High, high)]));
     } }
     example! {
         a: (include_str!("example1.txt"), 8000, 4000),
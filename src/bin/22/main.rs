@@ -2,34 +2,57 @@ use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::str::FromStr;
 use anyhow::*;
 use itertools::Itertools;
-use lazy_regex::regex_captures;
 
 use advent_2023::collect::MoreIntoIterator;
 use advent_2023::elapsed;
-use advent_2023::euclid::{Bounds as Bounds2d, point as point2d};
+use advent_2023::euclid3d::{bounds, vector, Bounds, Color, Format, Scene};
+use advent_2023::input;
+use advent_2023::solution::{run, Alternative, Part, Solution};
 
 fn main() -> Result<()> {
-    let mut input = parse_input(include_str!("input.txt"))?;
-    elapsed!(input.descend_all());
-
-    if std::env::args().len() > 1 {
-        println!("{}", input.openscad());
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    // e.g. --export scad to view the settled bricks in OpenSCAD
+    if args.first().map(|a| a.as_str()) == Some("--export") {
+        let format: Format = args.get(1).context("Missing format")?.parse()?;
+        let input = input::load(Day::DAY, args.get(2).map(|a| a.as_str()), Day::INPUT)?;
+        print!("{}", Day::parse(&input)?.settled().scene().export(format));
         return Ok(())
     }
 
-    let critical = input.critical_bricks();
-    println!("Non-critical bricks: {}", input.non_critical_bricks(&critical).count());
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 22;
+    const INPUT: &'static str = include_str!("input.txt");
 
-    if cfg!(feature="timing") {
-        println!("Unstable bricks (simulated): {}", elapsed!(input.simulate_unstable_bricks()).values().sum::<usize>());
+    type Input = Tower;
+
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    // Both parts need the settled tower; each settles its own copy so that the time spent settling
+    // is counted as solving, not parsing
+    fn part1(tower: &Self::Input) -> Result<String> {
+        let tower = tower.settled();
+        let critical = tower.critical_bricks();
+        Ok(tower.non_critical_bricks(&critical).count().to_string())
     }
 
-    println!("Unstable bricks: {}", elapsed!(input.find_all_unstable()).values().map(|v| v.len()).sum::<usize>());
+    fn part2(tower: &Self::Input) -> Result<String> {
+        let tower = tower.settled();
+        Ok(elapsed!(tower.find_all_unstable()).values().map(|v| v.len()).sum::<usize>().to_string())
+    }
 
-    Ok(())
+    fn alternatives() -> Vec<Alternative<Self::Input>> {
+        vec![Alternative{ part: Part::Two, name: "Simulated", solve: |tower|
+            Ok(elapsed!(tower.settled().simulate_unstable_bricks()).values().sum::<usize>().to_string()) }]
+    }
 }
 
-struct Tower {
+#[derive(Clone)]
+pub struct Tower {
     bricks: BTreeMap<i32, HashSet<Brick>>,
     supported_by: HashMap<usize, Vec<usize>>,
     supports: HashMap<usize, Vec<usize>>,
@@ -40,7 +63,7 @@ impl Tower {
         let mut bricks = BTreeMap::new();
         for (i, brick) in all_bricks.into_iter().enumerate() {
             let brick = Brick::create(i+1, brick);
-            bricks.entry(brick.z_top).or_insert_with(HashSet::new).insert(brick);
+            bricks.entry(brick.z_top()).or_insert_with(HashSet::new).insert(brick);
         }
         Tower{ bricks, supported_by: HashMap::new(), supports: HashMap::new() }
     }
@@ -50,11 +73,18 @@ impl Tower {
         let all_bricks = self.bricks.values().flat_map(|s| s.iter())
             .filter(|b| b.id != id);
         for brick in all_bricks {
-            bricks.entry(brick.z_top).or_insert_with(HashSet::new).insert(*brick);
+            bricks.entry(brick.z_top()).or_insert_with(HashSet::new).insert(*brick);
         }
         Tower{ bricks, supported_by: HashMap::new(), supports: HashMap::new() }
     }
 
+    // A copy of the tower after all the bricks have fallen
+    fn settled(&self) -> Tower {
+        let mut tower = self.clone();
+        elapsed!(tower.descend_all());
+        tower
+    }
+
     fn descend_all(&mut self) -> usize {
         let mut moved = 0;
         let top_row = self.bricks.last_key_value().map(|(k,_)| *k).unwrap();
@@ -76,32 +106,33 @@ impl Tower {
         let mut moved = 0;
         let bricks = self.bricks.remove(&row).unwrap_or_default();
         for mut brick in bricks {
-            debug_assert_eq!(brick.z_top, row);
+            debug_assert_eq!(brick.z_top(), row);
             self.descend_brick(&mut brick);
-            if brick.z_top != row { moved += 1; }
-            self.bricks.entry(brick.z_top).or_default().insert(brick);
+            if brick.z_top() != row { moved += 1; }
+            self.bricks.entry(brick.z_top()).or_default().insert(brick);
         }
         moved
     }
 
     fn descend_brick(&mut self, brick: &mut Brick) {
         // can't descend lower than height, which is 1 for horizontal bricks
-        for row in (brick.height..brick.z_top).rev() {
-            // look for collisions in the bottom row of the brick's height
-            let collisions = self.collisions(brick.bounds2d, row-brick.height+1);
+        let height = brick.bounds.size().z;
+        for row in (height..brick.z_top()).rev() {
+            let collisions = self.collisions(brick.at(row));
             if !collisions.is_empty() {
-                brick.z_top = row+1; // stay in the row above if there are collisions here
+                *brick = brick.at(row+1); // stay in the row above if there are collisions here
                 let prior = self.supported_by.insert(brick.id, collisions);
                 debug_assert!(prior.is_none());
                 return;
             }
         }
-        brick.z_top = brick.height; // nothing collided so stop at the floor
+        *brick = brick.at(height); // nothing collided so stop at the floor
     }
 
-    fn collisions(&self, bounds2d: Bounds2d, row: i32) -> Vec<usize> {
-        self.bricks.get(&row).iter().flat_map(|s| s.iter())
-            .filter(|b| b.bounds2d.intersects(bounds2d))
+    // Bricks that would overlap the bottom row of brick, which must not overlap any other rows
+    fn collisions(&self, brick: Brick) -> Vec<usize> {
+        self.bricks.get(&brick.bounds.min.z).iter().flat_map(|s| s.iter())
+            .filter(|b| b.bounds.intersects(brick.bounds))
             .map(|b| b.id)
             .collect()
     }
@@ -178,49 +209,45 @@ impl Tower {
         unstable
     }
 
-    fn openscad(&self) -> String {
-        let mut out = "module ocube(x1, y1, z1, x2, y2, z2) { translate([x1, y1, z1]) cube([x2-x1+1, y2-y1+1, z2-z1+1]); }\n\n".to_string();
-        for brick in self.bricks.values().flatten() {
-            let (x1, y1) = (brick.bounds2d.min.x, brick.bounds2d.min.y);
-            let (x2, y2) = (brick.bounds2d.max.x, brick.bounds2d.max.y);
-            out.push_str(&format!("ocube({},{},{}, {},{},{});\n", x1, y1, brick.z_top-brick.height+1, x2, y2, brick.z_top));
+    fn scene(&self) -> Scene {
+        let mut scene = Scene::new();
+        for brick in self.bricks.values().flatten().sorted_by_key(|b| b.id) {
+            scene.add(brick.bounds, Some(Color::indexed(brick.id)));
         }
-        out
+        scene
     }
 }
 
 #[derive(Debug, Hash, Eq, PartialEq, Copy, Clone)]
 struct Brick {
     id: usize,
-    bounds2d: Bounds2d,
-    z_top: i32,
-    height: i32,
+    bounds: Bounds,
 }
 
 impl Brick {
     fn create(id: usize, b: BrickStr) -> Brick {
-        Brick{ id, bounds2d: b.bounds2d, z_top: b.z_top, height: b.height }
+        Brick{ id, bounds: b.bounds }
+    }
+
+    fn z_top(&self) -> i32 { self.bounds.max.z }
+
+    // This brick moved up or down so that its top is in row z_top
+    fn at(&self, z_top: i32) -> Brick {
+        let offset = vector(0, 0, z_top - self.z_top());
+        Brick{ id: self.id, bounds: bounds(self.bounds.min + offset, self.bounds.max + offset) }
     }
 }
 #[derive(Debug)]
 struct BrickStr {
-    bounds2d: Bounds2d,
-    z_top: i32,
-    height: i32,
+    bounds: Bounds,
 }
 
 impl FromStr for BrickStr {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let (_, x1, y1, z1, x2, y2, z2) = regex_captures!(r"(\d+),(\d+),(\d+)~(\d+),(\d+),(\d+)", s)
-            .with_context(|| format!("Invalid: {}", s))?;
-        let a = point2d(x1.parse()?, y1.parse()?);
-        let b = point2d(x2.parse()?, y2.parse()?);
-        let (z1, z2): (i32, i32) = (z1.parse()?, z2.parse()?);
-        let z_top = std::cmp::max(z1, z2);
-        let height = (z2 - z1).abs() + 1;
-        Ok(BrickStr{ bounds2d: Bounds2d::from_points(&[a, b]).context("Invalid")?, z_top, height })
+        let (a, b) = s.split_once('~').with_context(|| format!("Invalid: {}", s))?;
+        Ok(BrickStr{ bounds: Bounds::from_points(&[a.parse()?, b.parse()?]).context("Invalid")? })
     }
 }
 
@@ -280,6 +307,10 @@ mod tests {
             .map(|(k, v)| (*k, v.len())).collect();
         assert_eq!(example.simulate_unstable_bricks(), expected_unstable_lens);
         assert_eq!(example.find_all_unstable(), expected_unstable);
+
+        let scene = example.scene().openscad();
+        assert_eq!(scene.lines().count(), 7);
+        assert_eq!(scene.lines().next(), Some("color(\"#3cb44b\") translate([1, 0, 1]) cube([1, 3, 1]);"));
     }
 
     #[test]
@@ -5,46 +5,66 @@ use anyhow::*;
 use itertools::Itertools;
 
 use advent_2023::pathfinding::{Edge, Graph, NodeGraph};
+use advent_2023::{input, timing};
+use advent_2023::solution::{run_parts, Solution};
 
 // Didn't implement https://en.wikipedia.org/wiki/Karger%27s_algorithm (per se), but it's related
 fn main() -> Result<()> {
     let args: Vec<_> = std::env::args().skip(1).collect();
-    if !args.is_empty() {
-        let passes: usize = args[0].parse()?;
-        let max: usize = args.get(1).map(|p| p.parse()).unwrap_or(Result::Ok(100))?;
-        let step_by: usize = args.get(2).map(|p| p.parse()).unwrap_or(Result::Ok(5))?;
-        return trials(passes, max, step_by);
+    if args.first().map(|a| a.as_str()) == Some("--trials") {
+        let passes: usize = args.get(1).context("--trials requires a number of passes")?.parse()?;
+        let max: usize = args.get(2).map(|p| p.parse()).unwrap_or(Result::Ok(100))?;
+        let step_by: usize = args.get(3).map(|p| p.parse()).unwrap_or(Result::Ok(5))?;
+        return trials(&input::load(Day::DAY, None, Day::INPUT)?, passes, max, step_by);
     }
 
-    let mut input: Components = include_str!("input.txt").parse()?;
-
+    // Read the input once, since it may come from stdin
+    let input = input::load(Day::DAY, args.first().map(|a| a.as_str()), Day::INPUT)?;
     if cfg!(debug_assertions) {
         let path = "25.gv";
-        std::fs::write(path, input.graphviz_undirected()?).expect("Unable to write DOT file");
+        std::fs::write(path, Day::parse(&input)?.graphviz_undirected()?).expect("Unable to write DOT file");
         println!("Wrote DOT file to {}", path);
     }
 
-    // From the above tests, 100 passes seems to yield the correct answer 99.9+% of the time
-    let edges = input.candidate_edges(100);
-    assert_eq!(edges.len(), 3);
-    input.remove_edges(&edges);
+    run_parts::<Day>(&input, None)?;
+    timing::report()
+}
 
-    let forest = input.forest();
-    assert_eq!(forest.len(), 2, "Expected two disjoint groups of nodes");
+pub struct Day;
 
-    println!("Product of component sizes: {}", forest.iter().map(|v| v.len()).product::<usize>());
+impl Solution for Day {
+    const DAY: u32 = 25;
+    const INPUT: &'static str = include_str!("input.txt");
+    const PART2: bool = false;
 
-    Ok(())
+    type Input = Components;
+
+    fn parse(input: &str) -> Result<Self::Input> { input.parse() }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        let mut components = input.clone();
+        // From the above tests, 100 passes seems to yield the correct answer 99.9+% of the time;
+        // the rng is seeded so the answer is stable for advent check
+        let edges = components.candidate_edges(100, &fastrand::Rng::with_seed(25));
+        assert_eq!(edges.len(), 3);
+        components.remove_edges(&edges);
+
+        let forest = components.forest();
+        assert_eq!(forest.len(), 2, "Expected two disjoint groups of nodes");
+
+        Ok(forest.iter().map(|v| v.len()).product::<usize>().to_string())
+    }
 }
 
-fn trials(passes: usize, max: usize, step_by: usize) -> Result<()> {
+fn trials(input: &str, passes: usize, max: usize, step_by: usize) -> Result<()> {
+    let rng = fastrand::Rng::new();
     for n in (step_by..=max).step_by(step_by) {
         let mut valid = 0;
         for _ in 0..passes {
-            let mut input: Components = include_str!("input.txt").parse()?;
-            let edges = input.candidate_edges(n);
-            input.remove_edges(&edges);
-            let forest = input.forest();
+            let mut components: Components = input.parse()?;
+            let edges = components.candidate_edges(n, &rng);
+            components.remove_edges(&edges);
+            let forest = components.forest();
             if forest.len() == 2 {
                 valid += 1;
             }
@@ -54,15 +74,16 @@ fn trials(passes: usize, max: usize, step_by: usize) -> Result<()> {
     Ok(())
 }
 
-#[derive(Debug)]
-struct Components {
+#[derive(Debug, Clone)]
+pub struct Components {
     edges: HashMap<Rc<str>, Vec<Rc<str>>>,
 }
 
 impl Components {
     fn create(connections: HashMap<&str, Vec<&str>>) -> Components {
         let mut components = Components{ edges: HashMap::new() };
-        for (source, dests) in connections {
+        // Sorted so the adjacency lists, and therefore the BFS routes, don't vary between runs
+        for (source, dests) in connections.into_iter().sorted() {
             let source = components.intern(source);
             for dest in dests {
                 let dest = components.intern(dest);
@@ -95,19 +116,22 @@ impl Components {
         counts
     }
 
-    fn repeated_necessary_edges(&self, n: usize) -> HashMap<(Rc<str>, Rc<str>), usize> {
+    // Sums the edges traversed by BFS from n randomly chosen nodes. The nodes are sorted before
+    // being shuffled so that a seeded rng yields the same edges on every run.
+    fn repeated_necessary_edges(&self, n: usize, rng: &fastrand::Rng) -> HashMap<(Rc<str>, Rc<str>), usize> {
+        let mut nodes: Vec<_> = self.edges.keys().sorted().collect();
+        rng.shuffle(&mut nodes);
         let mut ret = HashMap::new();
-        // This relies on HashMap iteration order being sufficiently random; this should be a safe
-        // assumption given existing implementation (notably
-        for (edge, count) in self.edges.keys().take(n).flat_map(|node| self.necessary_edges(node.as_ref()).into_iter()) {
+        for (edge, count) in nodes.into_iter().take(n).flat_map(|node| self.necessary_edges(node.as_ref()).into_iter()) {
             *ret.entry(edge).or_insert(0) += count;
         }
         ret
     }
 
-    fn candidate_edges(&self, n: usize) -> Vec<(Rc<str>, Rc<str>)> {
-        self.repeated_necessary_edges(n).into_iter()
-            .sorted_by_key(|(_, v)| *v).rev()
+    fn candidate_edges(&self, n: usize, rng: &fastrand::Rng) -> Vec<(Rc<str>, Rc<str>)> {
+        self.repeated_necessary_edges(n, rng).into_iter()
+            // Break ties by edge so the result doesn't depend on HashMap iteration order
+            .sorted_by(|(ka, va), (kb, vb)| vb.cmp(va).then_with(|| ka.cmp(kb)))
             .take(3)
             .map(|(k, _)| k)
             .collect()
@@ -174,7 +198,7 @@ mod tests {
         let mut input: Components = include_str!("example.txt").parse().unwrap();
 
         // 100 passes seems mostly sufficient to avoid false positives
-        let edges = input.candidate_edges(100);
+        let edges = input.candidate_edges(100, &fastrand::Rng::with_seed(25));
         assert_eq!(edges.len(), 3);
         for (source, dest) in edges {
             input.remove_edge(source.as_ref(), dest.as_ref());
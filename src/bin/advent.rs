@@ -0,0 +1,267 @@
+// Runs any or all of the days' solutions, e.g. `advent run 1..=25` or `advent run 17 --part 2`.
+// Each day is pulled in as a module so that the individual binaries remain the source of truth.
+use anyhow::*;
+
+use std::time::Duration;
+use advent_2023::bench::{self, Config};
+use advent_2023::cache::{self, InputCache};
+use advent_2023::scaffold::{self, Curl, Scaffold};
+use advent_2023::solution::{parse_days, Entry, Part};
+use advent_2023::timing;
+
+#[allow(dead_code)] #[path = "01/main.rs"] mod day01;
+#[allow(dead_code)] #[path = "02/main.rs"] mod day02;
+#[allow(dead_code)] #[path = "03/main.rs"] mod day03;
+#[allow(dead_code)] #[path = "04/main.rs"] mod day04;
+#[allow(dead_code)] #[path = "05/main.rs"] mod day05;
+#[allow(dead_code)] #[path = "06/main.rs"] mod day06;
+#[allow(dead_code)] #[path = "07/main.rs"] mod day07;
+#[allow(dead_code)] #[path = "08/main.rs"] mod day08;
+#[allow(dead_code)] #[path = "09/main.rs"] mod day09;
+#[allow(dead_code)] #[path = "10/main.rs"] mod day10;
+#[allow(dead_code)] #[path = "11/main.rs"] mod day11;
+#[allow(dead_code)] #[path = "12/main.rs"] mod day12;
+#[allow(dead_code)] #[path = "13/main.rs"] mod day13;
+#[allow(dead_code)] #[path = "14/main.rs"] mod day14;
+#[allow(dead_code)] #[path = "15/main.rs"] mod day15;
+#[allow(dead_code)] #[path = "17/main.rs"] mod day17;
+#[allow(dead_code)] #[path = "18/main.rs"] mod day18;
+#[allow(dead_code)] #[path = "19/main.rs"] mod day19;
+#[allow(dead_code)] #[path = "20/main.rs"] mod day20;
+#[allow(dead_code)] #[path = "22/main.rs"] mod day22;
+#[allow(dead_code)] #[path = "25/main.rs"] mod day25;
+
+fn registry() -> Vec<Entry> {
+    vec![
+        Entry::of::<day01::Day>(),
+        Entry::of::<day02::Day>(),
+        Entry::of::<day03::Day>(),
+        Entry::of::<day04::Day>(),
+        Entry::of::<day05::Day>(),
+        Entry::of::<day06::Day>(),
+        Entry::of::<day07::Day>(),
+        Entry::of::<day08::Day>(),
+        Entry::of::<day09::Day>(),
+        Entry::of::<day10::Day>(),
+        Entry::of::<day11::Day>(),
+        Entry::of::<day12::Day>(),
+        Entry::of::<day13::Day>(),
+        Entry::of::<day14::Day>(),
+        Entry::of::<day15::Day>(),
+        Entry::of::<day17::Day>(),
+        Entry::of::<day18::Day>(),
+        Entry::of::<day19::Day>(),
+        Entry::of::<day20::Day>(),
+        Entry::of::<day22::Day>(),
+        Entry::of::<day25::Day>(),
+    ]
+}
+
+const USAGE: &str = "Usage:
+    advent list
+    advent run [DAYS] [--part 1|2] [--input FILE]
+    advent check [DAYS]
+    advent bench [DAYS] [--warmup N] [--samples N]
+    advent new DAY [--html FILE] [--refresh]
+
+DAYS is a comma-separated list of days or ranges, e.g. 17, 1..=25, or 1,3..5 (default: all)
+FILE can only be used with a single day; use - to read from stdin. Otherwise inputs are read from
+$ADVENT_INPUT_DIR/NN.txt if it exists, falling back to the embedded input.txt.
+check compares each day's answers for its embedded input.txt against those recorded in answers.txt.
+bench prints Markdown tables of the mean time to run each day, broken down into parsing and each
+part, and compares the parts to any alternative implementations. Build with --release for
+meaningful results.
+new creates src/bin/DAY from the template, downloads the input and examples (add your session
+cookie to .cookie-jar), and registers the day here and in answers.txt. If the puzzle page has been
+saved (e.g. to get part 2's examples) pass it with --html. Inputs are cached in .input-cache; pass
+--refresh to download the input again.";
+
+fn main() -> Result<()> {
+    let args: Vec<_> = std::env::args().skip(1).collect();
+    match args.first().map(|a| a.as_str()) {
+        Some("list") => list(),
+        Some("run") => run(&args[1..])?,
+        Some("check") => check(&args[1..])?,
+        Some("bench") => bench(&args[1..])?,
+        Some("new") => new(&args[1..])?,
+        Some("help") | Some("--help") | None => println!("{}", USAGE),
+        Some(cmd) => bail!("Unknown command '{}'\n\n{}", cmd, USAGE),
+    }
+    timing::report()
+}
+
+fn list() {
+    let registry = registry();
+    let (present, missing): (Vec<_>, Vec<_>) = (1..=25)
+        .partition(|d| registry.iter().any(|e| e.day() == *d));
+    println!("Available: {:?}", present);
+    println!("Missing:   {:?}", missing);
+}
+
+fn run(args: &[String]) -> Result<()> {
+    let mut days = None;
+    let mut part = None;
+    let mut path = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" | "-p" => {
+                part = Some(args.next().context("--part requires a value")?.parse::<Part>()?);
+            },
+            "--input" | "-i" => {
+                path = Some(args.next().context("--input requires a value")?.as_str());
+            },
+            spec if days.is_none() => { days = Some(parse_days(spec)?); },
+            _ => bail!("Unexpected argument '{}'\n\n{}", arg, USAGE),
+        }
+    }
+    let days = days.unwrap_or_else(|| (1..=25).collect());
+
+    let registry = registry();
+    let entries: Vec<_> = days.iter()
+        .filter_map(|d| registry.iter().find(|e| e.day() == *d))
+        .filter(|e| part.map(|p| e.has_part(p)).unwrap_or(true))
+        .collect();
+    ensure!(!entries.is_empty(), "No solutions available for days {:?}", days);
+    ensure!(path.is_none() || entries.len() == 1, "--input requires a single day");
+    for entry in entries {
+        println!("{:?}", entry);
+        entry.run(&entry.load(path)?, part)?;
+    }
+    Ok(())
+}
+
+fn check(args: &[String]) -> Result<()> {
+    ensure!(args.len() <= 1, "Unexpected arguments {:?}\n\n{}", &args[1..], USAGE);
+    let days = args.first().map(|spec| parse_days(spec)).transpose()?.unwrap_or_else(|| (1..=25).collect());
+
+    let registry = registry();
+    let entries: Vec<_> = days.iter()
+        .filter_map(|d| registry.iter().find(|e| e.day() == *d))
+        .collect();
+    ensure!(!entries.is_empty(), "No solutions available for days {:?}", days);
+    // The days are independent, so check them concurrently; only the slowest day matters
+    let results: Vec<_> = std::thread::scope(|s| {
+        let handles: Vec<_> = entries.iter().map(|e| s.spawn(|| e.check())).collect();
+        handles.into_iter().map(|h| h.join().expect("Check panicked")).collect()
+    });
+
+    let mut failures = Vec::new();
+    for (entry, result) in entries.iter().zip(results) {
+        match result {
+            Result::Ok(outcomes) => {
+                for (part, outcome) in outcomes {
+                    println!("{:?} {}:\t{}", entry, part, outcome);
+                    if outcome.is_failure() { failures.push(format!("{:?} {}", entry, part)); }
+                }
+            },
+            Err(e) => {
+                println!("{:?}:\tERROR {:#}", entry, e);
+                failures.push(format!("{:?}", entry));
+            },
+        }
+    }
+    ensure!(failures.is_empty(), "Failed: {}", failures.join(", "));
+    Ok(())
+}
+
+fn bench(args: &[String]) -> Result<()> {
+    let mut days = None;
+    let mut config = Config::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--warmup" => {
+                config.warmup = args.next().context("--warmup requires a value")?.parse()?;
+            },
+            "--samples" => {
+                config.samples = args.next().context("--samples requires a value")?.parse()?;
+            },
+            spec if days.is_none() => { days = Some(parse_days(spec)?); },
+            _ => bail!("Unexpected argument '{}'\n\n{}", arg, USAGE),
+        }
+    }
+    let days = days.unwrap_or_else(|| (1..=25).collect());
+
+    let registry = registry();
+    let entries: Vec<_> = days.iter()
+        .filter_map(|d| registry.iter().find(|e| e.day() == *d))
+        .collect();
+    ensure!(!entries.is_empty(), "No solutions available for days {:?}", days);
+    let exe = std::env::current_exe()?;
+
+    println!("{}", bench::markdown_header(bench::SUMMARY_COLUMNS));
+    let mut accumulated = Duration::ZERO;
+    let mut stages = Vec::new();
+    let mut alternatives = Vec::new();
+    for entry in entries {
+        let report = entry.bench(&entry.load(None)?, &config)?;
+        // The individual binaries are built alongside this one
+        let binary_size = exe.with_file_name(format!("{:02}", entry.day())).metadata().ok().map(|m| m.len());
+        accumulated += report.runtime();
+        println!("{}", report.summary_row(binary_size, accumulated));
+        stages.extend(report.stage_rows());
+        alternatives.extend(report.alternative_rows());
+    }
+
+    println!("\n{}", bench::markdown_header(bench::STAGE_COLUMNS));
+    stages.iter().for_each(|row| println!("{}", row));
+
+    if !alternatives.is_empty() {
+        println!("\n{}", bench::markdown_header(bench::ALTERNATIVE_COLUMNS));
+        alternatives.iter().for_each(|row| println!("{}", row));
+    }
+    Ok(())
+}
+
+fn new(args: &[String]) -> Result<()> {
+    let mut day = None;
+    let mut html = None;
+    let mut refresh = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--html" => {
+                html = Some(args.next().context("--html requires a value")?);
+            },
+            "--refresh" => { refresh = true; },
+            d if day.is_none() => { day = Some(d.parse::<u32>().with_context(|| format!("Invalid day '{}'", d))?); },
+            _ => bail!("Unexpected argument '{}'\n\n{}", arg, USAGE),
+        }
+    }
+    let day = day.with_context(|| format!("DAY is required\n\n{}", USAGE))?;
+
+    let root = env!("CARGO_MANIFEST_DIR");
+    let scaffold = Scaffold::new(root, day)?;
+    let dir = scaffold.dir();
+    if scaffold.create()? {
+        println!("Created {}", dir.display());
+    } else {
+        println!("{} already exists, not overwriting", dir.display());
+    }
+
+    let curl = Curl::create(std::path::Path::new(root).join(".cookie-jar"))?;
+    let cache = InputCache::new(std::path::Path::new(root).join(".input-cache"), scaffold::YEAR, cache::DEFAULT_COOLDOWN);
+    if let Err(e) = scaffold.fetch_input(&cache, &curl, scaffold::BASE_URL, refresh) {
+        println!("Failed to fetch the puzzle input: {:#}", e);
+        if curl.missing_session() {
+            println!("The cookie jar seems incomplete; grab the session cookie from a browser and append it to .cookie-jar");
+        }
+    }
+
+    let examples = match html {
+        Some(html) => scaffold.write_examples(&std::fs::read_to_string(html)?),
+        None => scaffold.fetch_examples(&curl, scaffold::BASE_URL),
+    };
+    match examples {
+        Result::Ok(written) => written.iter().for_each(|p| println!("Wrote {}", p.display())),
+        Err(e) => println!("Failed to extract examples: {:#}", e),
+    }
+
+    scaffold.register()?;
+    println!("Registered day{:02} in src/bin/advent.rs and answers.txt", day);
+    println!("Run:\tcargo run --bin {:02}", day);
+    println!("Test:\tcargo test --bin {:02}", day);
+    println!("Runner:\tcargo run --bin advent -- run {}", day);
+    Ok(())
+}
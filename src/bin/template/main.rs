@@ -7,16 +7,35 @@ use anyhow::*;
 use lazy_regex::regex_captures;
 
 use advent_2023::collect::{MoreIntoIterator,MoreItertools};
+use advent_2023::solution::{run, Solution};
 
 fn main() -> Result<()> {
-    let input = parse_input(include_str!("input.txt"))?;
-    input.iter().for_each(|o| println!("{:?}", o));
+    run::<Day>()
+}
+
+pub struct Day;
+
+impl Solution for Day {
+    const DAY: u32 = 0;
+    const INPUT: &'static str = include_str!("input.txt");
+
+    type Input = Vec<Obj>;
 
-    Ok(())
+    fn parse(input: &str) -> Result<Self::Input> { parse_input(input) }
+
+    fn part1(input: &Self::Input) -> Result<String> {
+        Ok(format!("{:?}", input))
+    }
+
+    // advent check doesn't expect answers until they're added to answers.txt, so a placeholder
+    // keeps it passing while part 1 is in progress
+    fn part2(_input: &Self::Input) -> Result<String> {
+        Ok("TODO".into())
+    }
 }
 
 #[derive(Debug)]
-struct Obj {
+pub struct Obj {
     str: String,
 }
 
@@ -0,0 +1,278 @@
+// Caches downloaded puzzle inputs so that they're only fetched once, and validates them before
+// they're installed as a day's input.txt. Inputs are stored under their content hash alongside a
+// metadata file recording when they were fetched, e.g.:
+//   .input-cache/2023-07.meta
+//   .input-cache/objects/f5a3c2e8d1b4a697
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use anyhow::{bail, ensure, Context, Error, Result};
+
+use crate::scaffold::Fetcher;
+
+// How long to wait after a fetch before contacting the server again for the same day
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+
+// Checks that a downloaded input looks complete.
+pub fn validate(input: &str) -> Result<()> {
+    ensure!(!input.trim().is_empty(), "Input is empty");
+    ensure!(input.ends_with('\n'), "Input is missing a trailing newline, it may have been truncated");
+    Ok(())
+}
+
+// FNV-1a, which is stable across Rust versions unlike std's DefaultHasher
+fn content_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325_u64, |h, b| (h ^ b as u64).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+// Writes the input to path, via a temporary file so that a partial write is never left behind.
+pub fn install(input: &str, path: &Path) -> Result<()> {
+    validate(input)?;
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, input).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, path).with_context(|| format!("Failed to install {}", path.display()))?;
+    Ok(())
+}
+
+// Records the most recent fetch for a day; hash identifies the last input that was fetched
+// successfully, and is None if no fetch has succeeded yet.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Metadata {
+    pub year: u32,
+    pub day: u32,
+    pub fetched: SystemTime,
+    pub hash: Option<String>,
+}
+
+impl FromStr for Metadata {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (mut year, mut day, mut fetched, mut hash) = (None, None, None, None);
+        for line in s.lines() {
+            let (key, value) = line.split_once('=').with_context(|| format!("Invalid metadata: {}", line))?;
+            match key {
+                "year" => year = Some(value.parse()?),
+                "day" => day = Some(value.parse()?),
+                "fetched" => fetched = Some(UNIX_EPOCH + Duration::from_secs(value.parse()?)),
+                "hash" => hash = Some(value.to_string()),
+                _ => bail!("Unexpected metadata: {}", line),
+            }
+        }
+        Ok(Metadata{
+            year: year.context("Missing year")?,
+            day: day.context("Missing day")?,
+            fetched: fetched.context("Missing fetched")?,
+            hash,
+        })
+    }
+}
+
+impl fmt::Display for Metadata {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let fetched = self.fetched.duration_since(UNIX_EPOCH).map_err(|_| fmt::Error)?.as_secs();
+        writeln!(f, "year={}\nday={}\nfetched={}", self.year, self.day, fetched)?;
+        if let Some(hash) = &self.hash { writeln!(f, "hash={}", hash)?; }
+        Ok(())
+    }
+}
+
+pub struct InputCache {
+    dir: PathBuf,
+    year: u32,
+    cooldown: Duration,
+}
+
+impl InputCache {
+    pub fn new(dir: impl Into<PathBuf>, year: u32, cooldown: Duration) -> InputCache {
+        InputCache{ dir: dir.into(), year, cooldown }
+    }
+
+    fn metadata_path(&self, day: u32) -> PathBuf {
+        self.dir.join(format!("{}-{:02}.meta", self.year, day))
+    }
+
+    fn object_path(&self, hash: &str) -> PathBuf {
+        self.dir.join("objects").join(hash)
+    }
+
+    pub fn metadata(&self, day: u32) -> Result<Option<Metadata>> {
+        let path = self.metadata_path(day);
+        if !path.exists() { return Ok(None); }
+        let metadata = std::fs::read_to_string(&path)?.parse()
+            .with_context(|| format!("Invalid metadata in {}", path.display()))?;
+        Ok(Some(metadata))
+    }
+
+    // Returns the cached input for the day, if any. Fails if the cached copy has been corrupted.
+    pub fn get(&self, day: u32) -> Result<Option<String>> {
+        let hash = match self.metadata(day)?.and_then(|m| m.hash) {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+        let input = std::fs::read_to_string(self.object_path(&hash))
+            .with_context(|| format!("Cached input for day {} is missing", day))?;
+        ensure!(content_hash(&input) == hash, "Cached input for day {} does not match its hash", day);
+        Ok(Some(input))
+    }
+
+    // Returns the cached input for the day, or fetches it from url. If refresh is true the input is
+    // re-fetched even if it's cached. Either way the server won't be contacted more than once per
+    // cooldown, even if the previous fetch failed.
+    pub fn fetch(&self, day: u32, fetcher: &dyn Fetcher, url: &str, refresh: bool) -> Result<String> {
+        self.fetch_at(day, fetcher, url, refresh, SystemTime::now())
+    }
+
+    fn fetch_at(&self, day: u32, fetcher: &dyn Fetcher, url: &str, refresh: bool, now: SystemTime) -> Result<String> {
+        if !refresh {
+            if let Some(input) = self.get(day)? { return Ok(input); }
+        }
+        let previous = self.metadata(day)?;
+        if let Some(metadata) = &previous {
+            let elapsed = now.duration_since(metadata.fetched).unwrap_or(Duration::ZERO);
+            ensure!(elapsed >= self.cooldown, "Day {} was fetched {}s ago, wait {}s before trying again",
+                day, elapsed.as_secs(), (self.cooldown - elapsed).as_secs());
+        }
+
+        std::fs::create_dir_all(self.dir.join("objects"))?;
+        // A failed fetch only updates the fetch time, so any previously cached input is kept
+        let mut metadata = Metadata{ year: self.year, day, fetched: now, hash: previous.and_then(|m| m.hash) };
+        let result = fetcher.fetch(url).and_then(|input| validate(&input).map(|_| input));
+        if let Ok(input) = &result {
+            let hash = content_hash(input);
+            std::fs::write(self.object_path(&hash), input)?;
+            metadata.hash = Some(hash);
+        }
+        std::fs::write(self.metadata_path(day), metadata.to_string())?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct FakeFetcher {
+        responses: RefCell<Vec<Result<String>>>,
+    }
+
+    impl FakeFetcher {
+        fn new(responses: Vec<Result<String>>) -> FakeFetcher {
+            FakeFetcher{ responses: RefCell::new(responses.into_iter().rev().collect()) }
+        }
+    }
+
+    impl Fetcher for FakeFetcher {
+        fn fetch(&self, _url: &str) -> Result<String> {
+            self.responses.borrow_mut().pop().expect("Unexpected fetch")
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("advent-cache-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn at(secs: u64) -> SystemTime { UNIX_EPOCH + Duration::from_secs(secs) }
+
+    parameterized_test::create!{ validation, (input, valid), {
+        assert_eq!(validate(input).is_ok(), valid);
+    }}
+    validation! {
+        valid: ("1 2 3\n", true),
+        empty: ("", false),
+        blank: ("\n\n", false),
+        truncated: ("1 2 3\n4 5", false),
+    }
+
+    #[test]
+    fn metadata() {
+        let metadata = Metadata{ year: 2023, day: 7, fetched: at(1701925200), hash: Some("abc".into()) };
+        assert_eq!(metadata.to_string(), "year=2023\nday=7\nfetched=1701925200\nhash=abc\n");
+        assert_eq!(metadata.to_string().parse::<Metadata>().unwrap(), metadata);
+        let failed = Metadata{ hash: None, ..metadata };
+        assert_eq!(failed.to_string().parse::<Metadata>().unwrap(), failed);
+        assert!("year=2023\nday=7\n".parse::<Metadata>().is_err());
+    }
+
+    #[test]
+    fn hash() {
+        assert_eq!(content_hash(""), "cbf29ce484222325");
+        assert_ne!(content_hash("1\n"), content_hash("2\n"));
+    }
+
+    #[test]
+    fn cached() {
+        let dir = temp_dir("cached");
+        let cache = InputCache::new(&dir, 2023, Duration::from_secs(60));
+        // Only one fetch is expected
+        let fetcher = FakeFetcher::new(vec![Ok("input\n".into())]);
+
+        assert_eq!(cache.get(7).unwrap(), None);
+        assert_eq!(cache.fetch_at(7, &fetcher, "url", false, at(1000)).unwrap(), "input\n");
+        assert_eq!(cache.fetch_at(7, &fetcher, "url", false, at(1001)).unwrap(), "input\n");
+        assert_eq!(cache.get(7).unwrap().unwrap(), "input\n");
+        let metadata = cache.metadata(7).unwrap().unwrap();
+        assert_eq!((metadata.year, metadata.day, metadata.fetched), (2023, 7, at(1000)));
+
+        // Refreshing respects the cooldown
+        assert!(cache.fetch_at(7, &fetcher, "url", true, at(1030)).is_err());
+
+        // Corrupted inputs are detected
+        std::fs::write(cache.object_path(&metadata.hash.unwrap()), "inp").unwrap();
+        assert!(cache.get(7).is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn cooldown() {
+        let dir = temp_dir("cooldown");
+        let cache = InputCache::new(&dir, 2023, Duration::from_secs(60));
+        let fetcher = FakeFetcher::new(vec![Err(anyhow::anyhow!("Not found")), Ok("truncat".into()), Ok("input\n".into())]);
+
+        assert!(cache.fetch_at(7, &fetcher, "url", false, at(1000)).is_err());
+        // Too soon, the fetcher isn't called
+        let err = cache.fetch_at(7, &fetcher, "url", false, at(1030)).unwrap_err();
+        assert!(err.to_string().contains("wait 30s"), "{}", err);
+        // Invalid inputs aren't cached
+        assert!(cache.fetch_at(7, &fetcher, "url", false, at(1060)).is_err());
+        assert_eq!(cache.get(7).unwrap(), None);
+        assert_eq!(cache.fetch_at(7, &fetcher, "url", false, at(1120)).unwrap(), "input\n");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_refresh() {
+        let dir = temp_dir("refresh");
+        let cache = InputCache::new(&dir, 2023, Duration::from_secs(60));
+        let fetcher = FakeFetcher::new(vec![Ok("input\n".into()), Err(anyhow::anyhow!("Not found")), Ok("truncat".into())]);
+
+        assert_eq!(cache.fetch_at(7, &fetcher, "url", false, at(1000)).unwrap(), "input\n");
+        assert!(cache.fetch_at(7, &fetcher, "url", true, at(1060)).is_err());
+        assert!(cache.fetch_at(7, &fetcher, "url", true, at(1120)).is_err());
+        // The previous input is still cached, but the failed fetches still count towards the cooldown
+        assert_eq!(cache.get(7).unwrap().unwrap(), "input\n");
+        assert_eq!(cache.metadata(7).unwrap().unwrap().fetched, at(1120));
+        assert_eq!(cache.fetch_at(7, &fetcher, "url", false, at(1130)).unwrap(), "input\n");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn installs() {
+        let dir = temp_dir("install");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("input.txt");
+        assert!(install("truncat", &path).is_err());
+        assert!(!path.exists());
+        install("input\n", &path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "input\n");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -107,6 +107,7 @@ impl Range {
     pub fn start(&self) -> i64 { self.start }
     pub fn end(&self) -> i64 { self.end }
 
+    #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> u64 {
         (self.end - self.start) as u64
     }
@@ -1,6 +1,32 @@
 // Referenced https://docs.rs/rusttype/0.5.2/src/rusttype/geometry.rs.html
 // Other resources:
 //   https://crates.io/crates/euclid - https://doc.servo.org/src/euclid/point.rs.html
+mod coordinate {
+    use std::fmt;
+    use std::hash::Hash;
+    use std::num::ParseIntError;
+    use std::str::FromStr;
+
+    // The integer types Point, Vector, and Bounds can be made of. These default to i32, but larger
+    // types are useful for puzzles whose coordinates or areas would overflow.
+    pub trait Coordinate: num::PrimInt + num::Signed + num::Integer + Hash + Default + fmt::Debug + fmt::Display
+            + FromStr<Err = ParseIntError> {
+        const ZERO: Self;
+        const ONE: Self;
+    }
+
+    macro_rules! coordinate {
+        ($($t:ty),*) => { $(
+            impl Coordinate for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+            }
+        )* }
+    }
+    coordinate!(i32, i64, i128);
+}
+pub use self::coordinate::Coordinate;
+
 mod point {
     use super::*;
     use std::fmt;
@@ -10,96 +36,102 @@ mod point {
     use lazy_regex::regex_captures;
 
     #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    pub struct Point {
-        pub x: i32,
-        pub y: i32,
+    pub struct Point<T = i32> {
+        pub x: T,
+        pub y: T,
     }
 
     #[inline]
-    pub const fn point(x: i32, y: i32) -> Point {
+    pub const fn point<T>(x: T, y: T) -> Point<T> {
         Point { x, y }
     }
 
-    impl Point {
-        pub const ORIGIN: Point = point(0, 0);
+    impl<T: Coordinate> Point<T> {
+        pub const ORIGIN: Point<T> = point(T::ZERO, T::ZERO);
+
+        // Converts to a different coordinate type, e.g. to widen a Point before doing arithmetic
+        // that might overflow. Returns None if either coordinate doesn't fit.
+        pub fn cast<U: Coordinate>(&self) -> Option<Point<U>> {
+            Some(point(U::from(self.x)?, U::from(self.y)?))
+        }
     }
 
-    impl Add<&Vector> for Point {
-        type Output = Point;
+    impl<T: Coordinate> Add<&Vector<T>> for Point<T> {
+        type Output = Point<T>;
 
-        fn add(self, vec: &Vector) -> Point {
+        fn add(self, vec: &Vector<T>) -> Point<T> {
             point(self.x + vec.x, self.y + vec.y)
         }
     }
 
-    impl Add<&Vector> for &Point {
-        type Output = Point;
+    impl<T: Coordinate> Add<&Vector<T>> for &Point<T> {
+        type Output = Point<T>;
 
-        fn add(self, vec: &Vector) -> Point {
+        fn add(self, vec: &Vector<T>) -> Point<T> {
             point(self.x + vec.x, self.y + vec.y)
         }
     }
 
-    impl Add<Vector> for &Point {
-        type Output = Point;
+    impl<T: Coordinate> Add<Vector<T>> for &Point<T> {
+        type Output = Point<T>;
 
-        fn add(self, vec: Vector) -> Point {
+        fn add(self, vec: Vector<T>) -> Point<T> {
             point(self.x + vec.x, self.y + vec.y)
         }
     }
 
-    impl Add<Vector> for Point {
-        type Output = Point;
+    impl<T: Coordinate> Add<Vector<T>> for Point<T> {
+        type Output = Point<T>;
 
-        fn add(self, vec: Vector) -> Point {
+        fn add(self, vec: Vector<T>) -> Point<T> {
             point(self.x + vec.x, self.y + vec.y)
         }
     }
 
-    impl AddAssign<Vector> for Point {
-        fn add_assign(&mut self, vec: Vector) {
+    impl<T: Coordinate> AddAssign<Vector<T>> for Point<T> {
+        fn add_assign(&mut self, vec: Vector<T>) {
             *self = point(self.x + vec.x, self.y + vec.y);
         }
     }
 
-    impl AddAssign<&Vector> for Point {
-        fn add_assign(&mut self, vec: &Vector) {
+    impl<T: Coordinate> AddAssign<&Vector<T>> for Point<T> {
+        fn add_assign(&mut self, vec: &Vector<T>) {
             *self = point(self.x + vec.x, self.y + vec.y);
         }
     }
 
-    impl Sub for Point {
-        type Output = Vector;
+    impl<T: Coordinate> Sub for Point<T> {
+        type Output = Vector<T>;
 
-        fn sub(self, point: Point) -> Vector { vector(self.x - point.x, self.y - point.y) }
+        fn sub(self, point: Point<T>) -> Vector<T> { vector(self.x - point.x, self.y - point.y) }
     }
 
-    impl Sub for &Point {
-        type Output = Vector;
+    impl<T: Coordinate> Sub for &Point<T> {
+        type Output = Vector<T>;
 
-        fn sub(self, point: &Point) -> Vector { vector(self.x - point.x, self.y - point.y) }
+        fn sub(self, point: &Point<T>) -> Vector<T> { vector(self.x - point.x, self.y - point.y) }
     }
 
-    impl FromStr for Point {
+    impl<T: Coordinate> FromStr for Point<T> {
         type Err = Error;
 
         fn from_str(s: &str) -> Result<Self> {
             // r"^([^,]+),([^,]+)$" would be more strict - worth it?
             let (_, x, y) = regex_captures!(r"^\(?([^(,]+),([^),]+)\)?$", s)
                 .with_context(|| format!("Invalid point '{}'", s))?;
-            let x: i32 = x.trim().parse()?;
-            let y: i32 = y.trim().parse()?;
+            let x: T = x.trim().parse()?;
+            let y: T = y.trim().parse()?;
             Ok(point(x, y))
         }
     }
 
-    impl fmt::Debug for Point {
+    impl<T: fmt::Debug> fmt::Debug for Point<T> {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "({}, {})", self.x, self.y)
+            write!(f, "({:?}, {:?})", self.x, self.y)
         }
     }
 
-    impl fmt::Display for Point {
+    impl<T: fmt::Debug> fmt::Display for Point<T> {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             write!(f, "{:?}", self)
         }
@@ -117,6 +149,14 @@ mod point {
             assert_eq!("(-3, -5)".parse::<Point>().unwrap(), point(-3, -5));
 
             assert!("abc".parse::<Point>().is_err());
+            assert!("3000000000, 4".parse::<Point>().is_err());
+            assert_eq!("3000000000, 4".parse::<Point<i64>>().unwrap(), point(3000000000, 4));
+        }
+
+        #[test]
+        fn cast() {
+            assert_eq!(point(3, -4).cast::<i128>(), Some(point(3_i128, -4)));
+            assert_eq!(point(3000000000_i64, 4).cast::<i32>(), None);
         }
 
         #[test]
@@ -134,22 +174,35 @@ pub use self::point::{Point,point};
 mod bounds {
     use super::*;
 
+    use std::ops::RangeInclusive;
+
     #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
-    pub struct Bounds {
-        pub min: Point,
-        pub max: Point,
+    pub struct Bounds<T = i32> {
+        pub min: Point<T>,
+        pub max: Point<T>,
     }
 
+    // Comparisons aren't const for generic coordinates; use Bounds::new() in const contexts.
     #[inline]
-    pub const fn bounds(min: Point, max: Point) -> Bounds {
+    pub fn bounds<T: Coordinate>(min: Point<T>, max: Point<T>) -> Bounds<T> {
         assert!(min.x <= max.x);
         assert!(min.y <= max.y);
         Bounds{ min, max }
     }
 
     impl Bounds {
-        pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point>) -> Option<Bounds> {
-            let bound: Option<(Point, Point)> = points.into_iter().fold(None, |r , c|
+        // Equivalent to bounds(), but const; only available for the default i32 coordinates
+        #[inline]
+        pub const fn new(min: Point, max: Point) -> Bounds {
+            assert!(min.x <= max.x);
+            assert!(min.y <= max.y);
+            Bounds{ min, max }
+        }
+    }
+
+    impl<T: Coordinate> Bounds<T> {
+        pub fn from_points<'a>(points: impl IntoIterator<Item = &'a Point<T>>) -> Option<Bounds<T>> where T: 'a {
+            let bound: Option<(Point<T>, Point<T>)> = points.into_iter().fold(None, |r , c|
                 match r {
                     Some((min, max)) => {
                         Some((
@@ -163,24 +216,82 @@ mod bounds {
             bound.map(|(min, max)| bounds(min, max))
         }
 
-        pub fn contains(&self, pos: Point) -> bool {
+        pub fn contains(&self, pos: Point<T>) -> bool {
             self.min.x <= pos.x && self.min.y <= pos.y && self.max.x >= pos.x && self.max.y >= pos.y
         }
 
-        pub fn intersects(&self, other: Bounds) -> bool {
+        pub fn intersects(&self, other: Bounds<T>) -> bool {
             self.min.x <= other.max.x && self.max.x >= other.min.x && self.min.y <= other.max.y && self.max.y >= other.min.y
         }
 
-        pub fn size(&self) -> Vector { vector(self.max.x - self.min.x + 1, self.max.y - self.min.y + 1) }
+        pub fn size(&self) -> Vector<T> { vector(self.max.x - self.min.x + T::ONE, self.max.y - self.min.y + T::ONE) }
+
+        pub fn area(&self) -> T { let s = self.size(); s.x * s.y }
+
+        pub fn intersection(&self, other: Bounds<T>) -> Option<Bounds<T>> {
+            if !self.intersects(other) { return None; }
+            Some(bounds(
+                point(std::cmp::max(self.min.x, other.min.x), std::cmp::max(self.min.y, other.min.y)),
+                point(std::cmp::min(self.max.x, other.max.x), std::cmp::min(self.max.y, other.max.y))))
+        }
+
+        // The parts of self not in other, as up to four disjoint bounds
+        pub fn difference(&self, other: Bounds<T>) -> Vec<Bounds<T>> {
+            let overlap = match self.intersection(other) {
+                Some(overlap) => overlap,
+                None => return vec![*self],
+            };
+            let mut pieces = Vec::with_capacity(4);
+            // Full-width pieces above and below the overlap, then the pieces beside it
+            if self.min.y < overlap.min.y {
+                pieces.push(bounds(self.min, point(self.max.x, overlap.min.y - T::ONE)));
+            }
+            if overlap.max.y < self.max.y {
+                pieces.push(bounds(point(self.min.x, overlap.max.y + T::ONE), self.max));
+            }
+            if self.min.x < overlap.min.x {
+                pieces.push(bounds(point(self.min.x, overlap.min.y), point(overlap.min.x - T::ONE, overlap.max.y)));
+            }
+            if overlap.max.x < self.max.x {
+                pieces.push(bounds(point(overlap.max.x + T::ONE, overlap.min.y), point(self.max.x, overlap.max.y)));
+            }
+            pieces
+        }
+
+        // The number of points in any of the given bounds, counting points where they overlap once
+        pub fn union_area<'a>(all: impl IntoIterator<Item = &'a Bounds<T>>) -> T where T: 'a {
+            let mut disjoint: Vec<Bounds<T>> = Vec::new();
+            for b in all {
+                disjoint = disjoint.iter().flat_map(|d| d.difference(*b)).collect();
+                disjoint.push(*b);
+            }
+            disjoint.iter().fold(T::ZERO, |sum, b| sum + b.area())
+        }
+
+        // Grows the bounds by margin in every direction
+        pub fn expand(&self, margin: T) -> Bounds<T> {
+            assert!(!margin.is_negative(), "Use shrink() instead");
+            bounds(self.min + vector(-margin, -margin), self.max + vector(margin, margin))
+        }
 
-        pub fn area(&self) -> i32 { let s = self.size(); s.x * s.y }
+        // Shrinks the bounds by margin in every direction, or None if nothing would be left
+        pub fn shrink(&self, margin: T) -> Option<Bounds<T>> {
+            assert!(!margin.is_negative(), "Use expand() instead");
+            let (min, max) = (self.min + vector(margin, margin), self.max + vector(-margin, -margin));
+            if min.x > max.x || min.y > max.y { return None; }
+            Some(bounds(min, max))
+        }
 
-        pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
+        // Iteration relies on std's ranges, which support all the primitive integer types
+        // The iterators copy the bounds, rather than borrowing them
+        pub fn iter(&self) -> impl Iterator<Item = Point<T>> where RangeInclusive<T>: Iterator<Item = T> {
             self.iter_rows().flatten()
         }
 
-        pub fn iter_rows(&self) -> impl Iterator<Item = impl Iterator<Item = Point> + '_> {
-            (self.min.y..=self.max.y).map(move |y| (self.min.x..=self.max.x).map(move |x| point(x, y)))
+        pub fn iter_rows(&self) -> impl Iterator<Item = impl Iterator<Item = Point<T>>>
+                where RangeInclusive<T>: Iterator<Item = T> {
+            let (min, max) = (self.min, self.max);
+            (min.y..=max.y).map(move |y| (min.x..=max.x).map(move |x| point(x, y)))
         }
     }
 
@@ -195,6 +306,12 @@ mod bounds {
             assert_eq!(Bounds::from_points(&points), Some(bounds(point(0, 2), point(2, 5))));
         }
 
+        #[test]
+        fn const_bounds() {
+            const BOUNDS: Bounds = Bounds::new(point(0, 2), point(2, 5));
+            assert_eq!(BOUNDS, bounds(point(0, 2), point(2, 5)));
+        }
+
         #[test]
         fn in_bounds() {
             let zero_zero = point(0, 0);
@@ -226,6 +343,54 @@ mod bounds {
             let bound = bounds(point(-1, -1), point(2, 4));
             assert_eq!(bound.size(), vector(4, 6));
             assert_eq!(bound.area(), 4*6);
+
+            let large = bounds(point(0, 0), point(99999_i64, 99999));
+            assert_eq!(large.area(), 10000000000);
+        }
+
+        parameterized_test::create!{ intersections, (a, b, expected), {
+            assert_eq!(a.intersection(b), expected);
+            assert_eq!(b.intersection(a), expected);
+        }}
+        intersections! {
+            overlap: (bounds(point(0, 0), point(3, 3)), bounds(point(1, -1), point(4, 2)), Some(bounds(point(1, 0), point(3, 2)))),
+            inside: (bounds(point(0, 0), point(3, 3)), bounds(point(1, 1), point(2, 2)), Some(bounds(point(1, 1), point(2, 2)))),
+            corner: (bounds(point(0, 0), point(3, 3)), bounds(point(3, 3), point(5, 5)), Some(bounds(point(3, 3), point(3, 3)))),
+            disjoint: (bounds(point(0, 0), point(3, 3)), bounds(point(4, 2), point(5, 3)), None),
+        }
+
+        parameterized_test::create!{ differences, (a, b, pieces), {
+            let difference = a.difference(b);
+            assert_eq!(difference.len(), pieces);
+            assert_eq!(difference.iter().map(|d| d.area()).sum::<i32>(), a.area() - a.intersection(b).map(|i| i.area()).unwrap_or(0));
+            for (i, d) in difference.iter().enumerate() {
+                assert!(!d.intersects(b), "{:?}", d);
+                assert!(difference[i+1..].iter().all(|o| !d.intersects(*o)), "{:?}", difference);
+            }
+        }}
+        differences! {
+            disjoint: (bounds(point(0, 0), point(3, 3)), bounds(point(5, 5), point(6, 6)), 1),
+            covered: (bounds(point(1, 1), point(2, 2)), bounds(point(0, 0), point(3, 3)), 0),
+            hole: (bounds(point(0, 0), point(4, 4)), bounds(point(2, 2), point(2, 2)), 4),
+            corner: (bounds(point(0, 0), point(3, 3)), bounds(point(2, 2), point(5, 5)), 2),
+            edge: (bounds(point(0, 0), point(3, 3)), bounds(point(-1, 1), point(1, 2)), 3),
+        }
+
+        #[test]
+        fn union_area() {
+            let all = [bounds(point(0, 0), point(3, 3)), bounds(point(2, 2), point(5, 5)), bounds(point(1, 1), point(2, 2))];
+            assert_eq!(Bounds::union_area(&all), 16 + 16 - 4);
+            assert_eq!(Bounds::union_area(&all[..1]), 16);
+            assert_eq!(Bounds::<i64>::union_area(&[]), 0);
+        }
+
+        #[test]
+        fn margins() {
+            let bound = bounds(point(0, 0), point(3, 2));
+            assert_eq!(bound.expand(1), bounds(point(-1, -1), point(4, 3)));
+            assert_eq!(bound.shrink(1), Some(bounds(point(1, 1), point(2, 1))));
+            assert_eq!(bound.shrink(2), None);
+            assert_eq!(bound.expand(2).shrink(2), Some(bound));
         }
 
         #[test]
@@ -246,38 +411,41 @@ mod bounds {
 pub use self::bounds::{Bounds,bounds};
 
 mod vector {
+    use super::Coordinate;
     use std::fmt;
     use std::str::FromStr;
-    use std::ops::{Add,AddAssign,Mul};
+    use std::ops::{Add,AddAssign,Div,Mul,Neg,Sub};
     use anyhow::{Error, Result};
 
     #[derive(Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
-    pub struct Vector {
-        pub x: i32,
-        pub y: i32,
+    pub struct Vector<T = i32> {
+        pub x: T,
+        pub y: T,
     }
 
     #[inline]
-    pub const fn vector(x: i32, y: i32) -> Vector {
+    pub const fn vector<T>(x: T, y: T) -> Vector<T> {
         Vector { x, y }
     }
 
     impl Vector {
-        pub const ZERO: Vector = vector(0, 0);
-
         // https://en.wikipedia.org/wiki/Points_of_the_compass
         pub const CARDINAL: &'static [Vector] = &[
             vector(-1, 0), vector(0, -1), vector(1, 0), vector(0, 1)];
         pub const ORDINAL: &'static [Vector] = &[
             vector(-1, 0), vector(-1, -1), vector(0, -1), vector(1, -1),
             vector(1, 0), vector(1, 1), vector(0, 1), vector(-1, 1)];
+    }
+
+    impl<T: Coordinate> Vector<T> {
+        pub const ZERO: Vector<T> = vector(T::ZERO, T::ZERO);
 
         pub fn abs(&self) -> Self {
             vector(self.x.abs(), self.y.abs())
         }
 
         pub fn signum(&self) -> Self {
-            debug_assert!(self.x == 0 || self.y == 0 || self.x.abs() == self.y.abs(),
+            debug_assert!(self.x.is_zero() || self.y.is_zero() || self.x.abs() == self.y.abs(),
                           "{}.signum() is lossy; use signum_unchecked() if this is acceptable", self);
             self.signum_unchecked()
         }
@@ -287,71 +455,122 @@ mod vector {
         }
 
         pub fn len(&self) -> f64 {
-            (self.x as f64).hypot(self.y as f64)
+            self.x.to_f64().expect("Integer").hypot(self.y.to_f64().expect("Integer"))
+        }
+
+        pub fn grid_len(&self) -> T {
+            self.x.abs() + self.y.abs()
         }
 
-        pub fn grid_len(&self) -> u32 {
-            (self.x.abs() + self.y.abs()) as u32
+        // The number of squares a king would move, i.e. the Chebyshev distance
+        pub fn king_len(&self) -> T {
+            self.x.abs().max(self.y.abs())
         }
 
+        // The square of len(), which unlike len() is exact
+        pub fn len_squared(&self) -> T {
+            self.dot(*self)
+        }
+
+        pub fn dot(&self, other: Vector<T>) -> T {
+            self.x * other.x + self.y * other.y
+        }
+
+        // The z component of the 3D cross product; positive if other is counterclockwise from self
+        // with Cartesian axes, and zero if they're parallel.
+        pub fn cross(&self, other: Vector<T>) -> T {
+            self.x * other.y - self.y * other.x
+        }
+
+        pub fn cast<U: Coordinate>(&self) -> Option<Vector<U>> {
+            Some(vector(U::from(self.x)?, U::from(self.y)?))
+        }
+
+        // Quarter-turns with Cartesian axes, i.e. y increasing upwards. Where y increases downwards,
+        // as maps are usually drawn, these turn the other way; see Axes.
         pub fn left90(&self) -> Self { vector(-self.y, self.x) }
 
         pub fn right90(&self) -> Self { vector(self.y, -self.x) }
     }
 
-    impl Add<Vector> for Vector {
-        type Output = Vector;
+    impl<T: Coordinate> Add<Vector<T>> for Vector<T> {
+        type Output = Vector<T>;
 
         fn add(self, vec: Self) -> Self {
             vector(self.x + vec.x, self.y + vec.y)
         }
     }
 
-    impl Add<&Vector> for Vector {
-        type Output = Vector;
+    impl<T: Coordinate> Add<&Vector<T>> for Vector<T> {
+        type Output = Vector<T>;
 
         fn add(self, vec: &Self) -> Self {
             vector(self.x + vec.x, self.y + vec.y)
         }
     }
 
-    impl AddAssign<Vector> for Vector {
+    impl<T: Coordinate> AddAssign<Vector<T>> for Vector<T> {
         fn add_assign(&mut self, vec: Self) {
             *self = vector(self.x + vec.x, self.y + vec.y);
         }
     }
 
-    impl AddAssign<&Vector> for Vector {
+    impl<T: Coordinate> AddAssign<&Vector<T>> for Vector<T> {
         fn add_assign(&mut self, vec: &Self) {
             *self = vector(self.x + vec.x, self.y + vec.y);
         }
     }
 
-    impl Mul<i32> for Vector {
-        type Output = Vector;
+    impl<T: Coordinate> Sub<Vector<T>> for Vector<T> {
+        type Output = Vector<T>;
+
+        fn sub(self, vec: Self) -> Self {
+            vector(self.x - vec.x, self.y - vec.y)
+        }
+    }
+
+    impl<T: Coordinate> Neg for Vector<T> {
+        type Output = Vector<T>;
+
+        fn neg(self) -> Self {
+            vector(-self.x, -self.y)
+        }
+    }
+
+    impl<T: Coordinate> Mul<T> for Vector<T> {
+        type Output = Vector<T>;
 
-        fn mul(self, m: i32) -> Self {
+        fn mul(self, m: T) -> Self {
             vector(self.x * m, self.y * m)
         }
     }
 
-    impl FromStr for Vector {
+    // Integer division, so each component is truncated towards zero
+    impl<T: Coordinate> Div<T> for Vector<T> {
+        type Output = Vector<T>;
+
+        fn div(self, d: T) -> Self {
+            vector(self.x / d, self.y / d)
+        }
+    }
+
+    impl<T: Coordinate> FromStr for Vector<T> {
         type Err = Error;
 
         fn from_str(s: &str) -> Result<Self> {
             // Just reuse point's parser
-            let p: super::Point = s.parse()?;
+            let p: super::Point<T> = s.parse()?;
             Ok(vector(p.x, p.y))
         }
     }
 
-    impl fmt::Debug for Vector {
+    impl<T: fmt::Debug> fmt::Debug for Vector<T> {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            write!(f, "({}, {})", self.x, self.y)
+            write!(f, "({:?}, {:?})", self.x, self.y)
         }
     }
 
-    impl fmt::Display for Vector {
+    impl<T: fmt::Debug> fmt::Display for Vector<T> {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             write!(f, "{:?}", self)
         }
@@ -372,6 +591,15 @@ mod vector {
         #[test]
         fn len() {
             assert_approx_eq!(vector(3, -4).len(), 5_f64, f64::EPSILON);
+            assert_approx_eq!(vector(3_i128, -4).len(), 5_f64, f64::EPSILON);
+        }
+
+        #[test]
+        fn large() {
+            let v = vector(1000000_i64, -1000000) * 1000000;
+            assert_eq!(v.grid_len(), 2000000000000);
+            assert_eq!(v.abs(), vector(1000000000000, 1000000000000));
+            assert_eq!("1000000000000, 0".parse::<Vector<i64>>().unwrap(), vector(1000000000000, 0));
         }
 
         parameterized_test::create!{ grid_lens, (p1, p2, d), {
@@ -387,6 +615,39 @@ mod vector {
             f: (point(1,1), point(-1,-1), 4),
         }
 
+        parameterized_test::create!{ metrics, (v, king, squared), {
+            assert_eq!(v.king_len(), king);
+            assert_eq!((-v).king_len(), king);
+            assert_eq!(v.len_squared(), squared);
+            assert_approx_eq!((v.len_squared() as f64).sqrt(), v.len(), 1e-9);
+        }}
+        metrics! {
+            zero: (Vector::<i32>::ZERO, 0, 0),
+            straight: (vector(0, -4), 4, 16),
+            diagonal: (vector(3, 3), 3, 18),
+            knight: (vector(1, -2), 2, 5),
+            far: (vector(-8, 3), 8, 73),
+        }
+
+        #[test]
+        fn products() {
+            let (a, b) = (vector(2, 1), vector(-1, 3));
+            assert_eq!(a.dot(b), 1);
+            assert_eq!(a.dot(a.left90()), 0);
+            assert_eq!(a.cross(b), 7);
+            assert_eq!(b.cross(a), -7);
+            assert_eq!(a.cross(a * 3), 0);
+            assert!(a.cross(a.left90()) > 0);
+        }
+
+        #[test]
+        fn operators() {
+            assert_eq!(vector(2, 1) - vector(-1, 3), vector(3, -2));
+            assert_eq!(-vector(2, -1), vector(-2, 1));
+            assert_eq!(vector(6, -4) / 2, vector(3, -2));
+            assert_eq!(vector(7, -7) / 2, vector(3, -3));
+        }
+
         #[test]
         fn turns() {
             let v = vector(2, 1);
@@ -396,3 +657,1660 @@ mod vector {
     }
 }
 pub use self::vector::{Vector,vector};
+
+mod grid {
+    use super::*;
+    use std::fmt;
+    use std::ops::{Index, IndexMut};
+    use std::str::FromStr;
+    use anyhow::{ensure, Error, Result};
+
+    // A dense, rectangular grid of values, stored row-by-row. Lookups are considerably cheaper
+    // than a HashMap<Point, T>, at the cost of storing a value for every point in the bounds.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Grid<T> {
+        bounds: Bounds,
+        cells: Vec<T>,
+    }
+
+    impl<T> Grid<T> {
+        pub fn from_fn(bounds: Bounds, f: impl FnMut(Point) -> T) -> Grid<T> {
+            Grid{ bounds, cells: bounds.iter().map(f).collect() }
+        }
+
+        // Parses a character map, with the top-left character at the origin, converting each
+        // character with f. Every line must be the same length.
+        pub fn parse(s: &str, mut f: impl FnMut(char) -> Result<T>) -> Result<Grid<T>> {
+            let mut cells = Vec::with_capacity(s.len());
+            let mut width = None;
+            let mut height = 0;
+            for line in s.lines() {
+                let len = line.chars().count();
+                ensure!(*width.get_or_insert(len) == len, "Ragged grid, line {} has length {} not {}", height, len, width.unwrap_or(0));
+                for c in line.chars() {
+                    cells.push(f(c)?);
+                }
+                height += 1;
+            }
+            ensure!(width.unwrap_or(0) > 0, "Empty grid");
+            let width = width.expect("Non-empty") as i32;
+            Ok(Grid{ bounds: bounds(Point::ORIGIN, point(width - 1, height - 1)), cells })
+        }
+
+        pub fn bounds(&self) -> Bounds { self.bounds }
+
+        fn width(&self) -> usize { self.bounds.size().x as usize }
+
+        fn index_of(&self, pos: Point) -> Option<usize> {
+            if !self.bounds.contains(pos) { return None; }
+            let offset = pos - self.bounds.min;
+            Some(offset.y as usize * self.width() + offset.x as usize)
+        }
+
+        pub fn get(&self, pos: Point) -> Option<&T> {
+            self.index_of(pos).map(|i| &self.cells[i])
+        }
+
+        pub fn get_mut(&mut self, pos: Point) -> Option<&mut T> {
+            self.index_of(pos).map(|i| &mut self.cells[i])
+        }
+
+        // All points and their values, row-by-row
+        pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> + '_ {
+            self.bounds.iter().zip(self.cells.iter())
+        }
+
+        pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+            self.cells.chunks(self.width())
+        }
+
+        pub fn row(&self, y: i32) -> Option<&[T]> {
+            let start = self.index_of(point(self.bounds.min.x, y))?;
+            Some(&self.cells[start..start + self.width()])
+        }
+
+        pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+            (self.bounds.min.x..=self.bounds.max.x).map(move |x| self.column(x).expect("In bounds"))
+        }
+
+        pub fn column(&self, x: i32) -> Option<impl Iterator<Item = &T> + '_> {
+            let start = self.index_of(point(x, self.bounds.min.y))?;
+            Some(self.cells[start..].iter().step_by(self.width()))
+        }
+
+        fn neighbors<'a>(&'a self, pos: Point, dirs: &'static [Vector]) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+            dirs.iter().map(move |d| pos + d).filter_map(move |p| self.get(p).map(|v| (p, v)))
+        }
+
+        // The in-bounds neighbors of pos in the four cardinal directions
+        pub fn cardinal_neighbors(&self, pos: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+            self.neighbors(pos, Vector::CARDINAL)
+        }
+
+        // The in-bounds neighbors of pos in all eight directions
+        pub fn ordinal_neighbors(&self, pos: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+            self.neighbors(pos, Vector::ORDINAL)
+        }
+    }
+
+    impl<T> Index<Point> for Grid<T> {
+        type Output = T;
+
+        fn index(&self, pos: Point) -> &T {
+            self.get(pos).unwrap_or_else(|| panic!("{} is outside {:?}", pos, self.bounds))
+        }
+    }
+
+    impl<T> IndexMut<Point> for Grid<T> {
+        fn index_mut(&mut self, pos: Point) -> &mut T {
+            let bounds = self.bounds;
+            self.get_mut(pos).unwrap_or_else(|| panic!("{} is outside {:?}", pos, bounds))
+        }
+    }
+
+    // Types that can be parsed from a single character, e.g. an enum of map tiles, can be parsed
+    // directly. Otherwise use Grid::parse().
+    impl<T: TryFrom<char>> FromStr for Grid<T> where T::Error: Into<Error> {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            Grid::parse(s, |c| T::try_from(c).map_err(|e| e.into()))
+        }
+    }
+
+    impl<T: fmt::Display> fmt::Display for Grid<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for (i, row) in self.rows().enumerate() {
+                if i > 0 { writeln!(f)?; }
+                for v in row { write!(f, "{}", v)?; }
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use anyhow::Context;
+
+        fn example() -> Grid<u32> {
+            Grid::parse("123\n456\n", |c| c.to_digit(10).context("Not a digit")).unwrap()
+        }
+
+        #[test]
+        fn parse() {
+            let grid = example();
+            assert_eq!(grid.bounds(), bounds(point(0, 0), point(2, 1)));
+            assert_eq!(grid.get(point(0, 0)), Some(&1));
+            assert_eq!(grid[point(2, 1)], 6);
+            assert_eq!(grid.get(point(3, 0)), None);
+            assert_eq!(grid.get(point(0, -1)), None);
+
+            assert!(Grid::parse("12\n3\n", Ok).is_err());
+            assert!(Grid::parse("", Ok).is_err());
+            assert!(Grid::parse("12a\n", |c| c.to_digit(10).context("Not a digit")).is_err());
+        }
+
+        #[test]
+        fn from_str() {
+            let grid: Grid<char> = "ab\ncd".parse().unwrap();
+            assert_eq!(grid[point(1, 1)], 'd');
+            assert_eq!(grid.to_string(), "ab\ncd");
+        }
+
+        #[test]
+        fn from_fn() {
+            let grid = Grid::from_fn(bounds(point(-1, -1), point(1, 0)), |p| p.x * p.y);
+            assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 0, -1], [0, 0, 0]]);
+            assert_eq!(grid[point(1, -1)], -1);
+        }
+
+        #[test]
+        fn mutate() {
+            let mut grid = example();
+            *grid.get_mut(point(1, 0)).unwrap() = 9;
+            grid[point(2, 1)] += 10;
+            assert!(grid.get_mut(point(5, 5)).is_none());
+            assert_eq!(grid.to_string(), "193\n4516");
+        }
+
+        #[test]
+        fn rows_and_columns() {
+            let grid = example();
+            assert_eq!(grid.rows().collect::<Vec<_>>(), [[1, 2, 3], [4, 5, 6]]);
+            assert_eq!(grid.row(1), Some(&[4, 5, 6][..]));
+            assert_eq!(grid.row(2), None);
+            assert_eq!(grid.columns().map(|c| c.copied().collect::<Vec<_>>()).collect::<Vec<_>>(), [[1, 4], [2, 5], [3, 6]]);
+            assert_eq!(grid.column(1).unwrap().collect::<Vec<_>>(), [&2, &5]);
+            assert!(grid.column(3).is_none());
+            assert_eq!(grid.iter().map(|(p, v)| (p, *v)).last(), Some((point(2, 1), 6)));
+        }
+
+        #[test]
+        fn neighbors() {
+            let grid = example();
+            assert_eq!(grid.cardinal_neighbors(point(0, 0)).collect::<Vec<_>>(), [(point(1, 0), &2), (point(0, 1), &4)]);
+            assert_eq!(grid.ordinal_neighbors(point(1, 0)).map(|(_, v)| *v).collect::<Vec<_>>(), [1, 3, 6, 5, 4]);
+        }
+    }
+}
+pub use self::grid::Grid;
+
+mod tiled_grid {
+    use super::*;
+
+    // A Grid that repeats infinitely in every direction, e.g. for maps that tile. Any point can be
+    // looked up, and is mapped back onto the underlying grid.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct TiledGrid<T> {
+        grid: Grid<T>,
+    }
+
+    impl<T> TiledGrid<T> {
+        pub fn new(grid: Grid<T>) -> TiledGrid<T> {
+            TiledGrid{ grid }
+        }
+
+        pub fn grid(&self) -> &Grid<T> { &self.grid }
+
+        // The tile pos is in, where the underlying grid is tile (0, 0), the tile to its right is
+        // (1, 0), and so on.
+        pub fn tile(&self, pos: Point) -> Point {
+            let offset = pos - self.grid.bounds().min;
+            let size = self.grid.bounds().size();
+            point(offset.x.div_euclid(size.x), offset.y.div_euclid(size.y))
+        }
+
+        // The point in the underlying grid corresponding to pos
+        pub fn wrap(&self, pos: Point) -> Point {
+            let bounds = self.grid.bounds();
+            let offset = pos - bounds.min;
+            let size = bounds.size();
+            bounds.min + vector(offset.x.rem_euclid(size.x), offset.y.rem_euclid(size.y))
+        }
+
+        pub fn get(&self, pos: Point) -> &T {
+            &self.grid[self.wrap(pos)]
+        }
+
+        // Sets the value at pos, which affects the corresponding point in every tile.
+        pub fn get_mut(&mut self, pos: Point) -> &mut T {
+            let pos = self.wrap(pos);
+            &mut self.grid[pos]
+        }
+
+        // All points in the window, which may extend past the underlying grid, and their values
+        pub fn iter(&self, window: Bounds) -> impl Iterator<Item = (Point, &T)> + '_ {
+            window.iter().map(move |p| (p, self.get(p)))
+        }
+
+        pub fn cardinal_neighbors(&self, pos: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+            Vector::CARDINAL.iter().map(move |d| pos + d).map(move |p| (p, self.get(p)))
+        }
+
+        pub fn ordinal_neighbors(&self, pos: Point) -> impl Iterator<Item = (Point, &T)> + '_ {
+            Vector::ORDINAL.iter().map(move |d| pos + d).map(move |p| (p, self.get(p)))
+        }
+    }
+
+    impl<T> From<Grid<T>> for TiledGrid<T> {
+        fn from(grid: Grid<T>) -> Self { TiledGrid::new(grid) }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn example() -> TiledGrid<char> {
+            TiledGrid::new("ab\ncd\nef".parse().unwrap())
+        }
+
+        parameterized_test::create!{ tiles, (pos, tile, wrapped, value), {
+            let grid = example();
+            assert_eq!(grid.tile(pos), tile);
+            assert_eq!(grid.wrap(pos), wrapped);
+            assert_eq!(*grid.get(pos), value);
+        }}
+        tiles! {
+            origin: (point(0, 0), point(0, 0), point(0, 0), 'a'),
+            inside: (point(1, 2), point(0, 0), point(1, 2), 'f'),
+            right: (point(2, 0), point(1, 0), point(0, 0), 'a'),
+            below: (point(1, 3), point(0, 1), point(1, 0), 'b'),
+            left: (point(-1, 0), point(-1, 0), point(1, 0), 'b'),
+            above: (point(0, -1), point(0, -1), point(0, 2), 'e'),
+            far: (point(-5, 7), point(-3, 2), point(1, 1), 'd'),
+        }
+
+        #[test]
+        fn offset_bounds() {
+            let grid = TiledGrid::new(Grid::from_fn(bounds(point(-1, -1), point(0, 0)), |p| p));
+            assert_eq!(grid.tile(point(-1, -1)), point(0, 0));
+            assert_eq!(grid.tile(point(1, 0)), point(1, 0));
+            assert_eq!(grid.tile(point(-2, -3)), point(-1, -1));
+            assert_eq!(*grid.get(point(1, 1)), point(-1, -1));
+        }
+
+        #[test]
+        fn window() {
+            let grid = example();
+            let window: String = grid.iter(bounds(point(-1, -1), point(2, -1))).map(|(_, c)| c).collect();
+            assert_eq!(window, "fefe");
+            assert_eq!(grid.iter(bounds(point(-3, -3), point(3, 3))).count(), 49);
+        }
+
+        #[test]
+        fn mutate() {
+            let mut grid = example();
+            *grid.get_mut(point(-2, 3)) = 'z';
+            assert_eq!(*grid.get(point(0, 0)), 'z');
+            assert_eq!(grid.grid()[point(0, 0)], 'z');
+        }
+
+        #[test]
+        fn neighbors() {
+            let grid = example();
+            assert_eq!(grid.cardinal_neighbors(point(0, 0)).map(|(_, c)| *c).collect::<String>(), "bebc");
+            assert_eq!(grid.ordinal_neighbors(point(0, 0)).count(), 8);
+        }
+    }
+}
+pub use self::tiled_grid::TiledGrid;
+
+mod transform {
+    use super::*;
+
+    // The rotations and reflections of the plane about the origin. Applying the same transform to
+    // e.g. a Grid allows direction-specific logic to be written once, say for the north edge, and
+    // then reused for the other directions.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum Transform {
+        Identity,
+        // Quarter-turns, consistent with Vector::right90()
+        Rotate90,
+        Rotate180,
+        Rotate270,
+        // Swaps x and y, i.e. reflects across the line x = y
+        Transpose,
+        // Reflects across the y axis, i.e. negates x
+        FlipHorizontal,
+        // Reflects across the x axis, i.e. negates y
+        FlipVertical,
+    }
+
+    impl Transform {
+        pub const ROTATIONS: &'static [Transform] = &[
+            Transform::Identity, Transform::Rotate90, Transform::Rotate180, Transform::Rotate270];
+
+        pub fn inverse(&self) -> Transform {
+            match self {
+                Transform::Rotate90 => Transform::Rotate270,
+                Transform::Rotate270 => Transform::Rotate90,
+                t => *t,
+            }
+        }
+
+        // The rotation that turns dir to face the same way as target, if any
+        pub fn rotation_between(dir: Vector, target: Vector) -> Option<Transform> {
+            Transform::ROTATIONS.iter().find(|t| t.vector(dir) == target).copied()
+        }
+
+        pub fn vector<C: Coordinate>(&self, v: Vector<C>) -> Vector<C> {
+            match self {
+                Transform::Identity => v,
+                Transform::Rotate90 => vector(v.y, -v.x),
+                Transform::Rotate180 => vector(-v.x, -v.y),
+                Transform::Rotate270 => vector(-v.y, v.x),
+                Transform::Transpose => vector(v.y, v.x),
+                Transform::FlipHorizontal => vector(-v.x, v.y),
+                Transform::FlipVertical => vector(v.x, -v.y),
+            }
+        }
+
+        pub fn point<C: Coordinate>(&self, p: Point<C>) -> Point<C> {
+            Point::ORIGIN + self.vector(p - Point::ORIGIN)
+        }
+
+        pub fn bounds<C: Coordinate>(&self, b: Bounds<C>) -> Bounds<C> {
+            Bounds::from_points(&[self.point(b.min), self.point(b.max)]).expect("Non-empty")
+        }
+
+        pub fn points<'a, C: Coordinate + 'a, B: FromIterator<Point<C>>>(&self, points: impl IntoIterator<Item = &'a Point<C>>) -> B {
+            points.into_iter().map(|p| self.point(*p)).collect()
+        }
+
+        // Moves each value in the grid to its transformed point; the resulting grid's bounds are
+        // the transformed bounds.
+        pub fn grid<T: Clone>(&self, grid: &Grid<T>) -> Grid<T> {
+            let inverse = self.inverse();
+            Grid::from_fn(self.bounds(grid.bounds()), |p| grid[inverse.point(p)].clone())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashSet;
+
+        const ALL: &[Transform] = &[
+            Transform::Identity, Transform::Rotate90, Transform::Rotate180, Transform::Rotate270,
+            Transform::Transpose, Transform::FlipHorizontal, Transform::FlipVertical];
+
+        parameterized_test::create!{ points, (transform, expected), {
+            assert_eq!(transform.point(point(1, 2)), expected);
+            assert_eq!(transform.inverse().point(expected), point(1, 2));
+        }}
+        points! {
+            identity: (Transform::Identity, point(1, 2)),
+            rotate90: (Transform::Rotate90, point(2, -1)),
+            rotate180: (Transform::Rotate180, point(-1, -2)),
+            rotate270: (Transform::Rotate270, point(-2, 1)),
+            transpose: (Transform::Transpose, point(2, 1)),
+            flip_horizontal: (Transform::FlipHorizontal, point(-1, 2)),
+            flip_vertical: (Transform::FlipVertical, point(1, -2)),
+        }
+
+        #[test]
+        fn consistent_with_vector() {
+            for v in Vector::ORDINAL {
+                assert_eq!(Transform::Rotate90.vector(*v), v.right90());
+                assert_eq!(Transform::Rotate270.vector(*v), v.left90());
+            }
+        }
+
+        #[test]
+        fn rotation_between() {
+            for dir in Vector::CARDINAL {
+                let rotation = Transform::rotation_between(*dir, vector(0, -1)).unwrap();
+                assert_eq!(rotation.vector(*dir), vector(0, -1));
+            }
+            assert_eq!(Transform::rotation_between(vector(1, 0), vector(1, 1)), None);
+        }
+
+        #[test]
+        fn bounds_and_points() {
+            let points = [point(1, 2), point(3, 5)];
+            let b = Bounds::from_points(&points).unwrap();
+            for t in ALL {
+                let transformed: HashSet<Point> = t.points(&points);
+                assert_eq!(Bounds::from_points(&transformed), Some(t.bounds(b)), "{:?}", t);
+            }
+            assert_eq!(Transform::Rotate90.bounds(b), bounds(point(2, -3), point(5, -1)));
+        }
+
+        #[test]
+        fn grids() {
+            let grid: Grid<char> = "abc\ndef".parse().unwrap();
+            assert_eq!(Transform::Rotate90.grid(&grid).to_string(), "cf\nbe\nad");
+            assert_eq!(Transform::Rotate180.grid(&grid).to_string(), "fed\ncba");
+            assert_eq!(Transform::Rotate270.grid(&grid).to_string(), "da\neb\nfc");
+            assert_eq!(Transform::Transpose.grid(&grid).to_string(), "ad\nbe\ncf");
+            assert_eq!(Transform::FlipHorizontal.grid(&grid).to_string(), "cba\nfed");
+            assert_eq!(Transform::FlipVertical.grid(&grid).to_string(), "def\nabc");
+            for t in ALL {
+                assert_eq!(t.inverse().grid(&t.grid(&grid)), grid, "{:?}", t);
+            }
+        }
+    }
+}
+pub use self::transform::Transform;
+
+mod polygon {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum Orientation {
+        Clockwise,
+        Counterclockwise,
+    }
+
+    // A polygon whose vertices are lattice points, e.g. a loop traced through a grid. Orientation
+    // and winding follow the mathematical convention where y increases upwards, so a polygon that
+    // appears clockwise when y is drawn increasing downwards (as puzzle maps are) is
+    // Counterclockwise here.
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Polygon<T = i32> {
+        vertices: Vec<Point<T>>,
+    }
+
+    impl<T: Coordinate> Polygon<T> {
+        // The last vertex is implicitly connected back to the first, so repeating the first vertex
+        // at the end is optional.
+        pub fn new(mut vertices: Vec<Point<T>>) -> Polygon<T> {
+            if vertices.len() > 1 && vertices.first() == vertices.last() {
+                vertices.pop();
+            }
+            assert!(!vertices.is_empty(), "A polygon needs at least one vertex");
+            Polygon{ vertices }
+        }
+
+        // Traces a polygon by following each move in turn, starting from start.
+        pub fn from_moves(start: Point<T>, moves: impl IntoIterator<Item = Vector<T>>) -> Polygon<T> {
+            let mut vertices = vec![start];
+            let mut pos = start;
+            for step in moves {
+                pos += step;
+                vertices.push(pos);
+            }
+            Polygon::new(vertices)
+        }
+
+        pub fn vertices(&self) -> &[Point<T>] { &self.vertices }
+
+        // Each edge, including the one from the last vertex back to the first
+        pub fn edges(&self) -> impl Iterator<Item = (Point<T>, Point<T>)> + '_ {
+            self.vertices.iter().copied().zip(self.vertices.iter().copied().cycle().skip(1))
+        }
+
+        // https://en.wikipedia.org/wiki/Shoelace_formula
+        // Doubled so that it's always an integer; positive if the polygon is counterclockwise.
+        pub fn twice_signed_area(&self) -> T {
+            self.edges().fold(T::ZERO, |sum, (a, b)| sum + (a - Point::ORIGIN).cross(b - Point::ORIGIN))
+        }
+
+        // None if the polygon has no area, e.g. because all its vertices are on a line
+        pub fn orientation(&self) -> Option<Orientation> {
+            let area = self.twice_signed_area();
+            if area.is_positive() { Some(Orientation::Counterclockwise) }
+            else if area.is_negative() { Some(Orientation::Clockwise) }
+            else { None }
+        }
+
+        // The number of lattice points on the polygon's edges
+        pub fn boundary_points(&self) -> T {
+            self.edges().fold(T::ZERO, |sum, (a, b)| { let d = b - a; sum + d.x.gcd(&d.y) })
+        }
+
+        // The number of lattice points strictly inside the polygon, which must not intersect
+        // itself. https://en.wikipedia.org/wiki/Pick%27s_theorem
+        // None if the polygon has no area (see orientation()), since its edges then retrace each
+        // other and Pick's theorem doesn't apply.
+        pub fn interior_points(&self) -> Option<T> {
+            self.orientation()?;
+            let two = T::ONE + T::ONE;
+            Some((self.twice_signed_area().abs() - self.boundary_points() + two) / two)
+        }
+
+        // The number of lattice points on or inside the polygon, e.g. the number of grid cells a
+        // loop through the grid encloses, including the loop itself. None if the polygon has no
+        // area.
+        pub fn lattice_points(&self) -> Option<T> {
+            Some(self.boundary_points() + self.interior_points()?)
+        }
+
+        // The number of times the polygon winds counterclockwise around pos, or negative if it
+        // winds clockwise. Not meaningful for points on the boundary.
+        // https://en.wikipedia.org/wiki/Point_in_polygon#Winding_number_algorithm
+        pub fn winding_number(&self, pos: Point<T>) -> i32 {
+            let mut winding = 0;
+            for (a, b) in self.edges() {
+                let side = (b - a).cross(pos - a);
+                if a.y <= pos.y {
+                    if b.y > pos.y && side.is_positive() { winding += 1; }
+                } else if b.y <= pos.y && side.is_negative() {
+                    winding -= 1;
+                }
+            }
+            winding
+        }
+
+        pub fn on_boundary(&self, pos: Point<T>) -> bool {
+            self.edges().any(|(a, b)| segment(a, b).contains(pos))
+        }
+
+        // True if pos is on the boundary or inside the polygon
+        pub fn contains(&self, pos: Point<T>) -> bool {
+            self.on_boundary(pos) || self.winding_number(pos) != 0
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn square() -> Polygon {
+            Polygon::new(vec![point(0, 0), point(2, 0), point(2, 2), point(0, 2)])
+        }
+
+        #[test]
+        fn construction() {
+            let closed = Polygon::new(vec![point(0, 0), point(2, 0), point(2, 2), point(0, 2), point(0, 0)]);
+            assert_eq!(closed, square());
+            let moves = Polygon::from_moves(point(0, 0), [vector(2, 0), vector(0, 2), vector(-2, 0), vector(0, -2)]);
+            assert_eq!(moves, square());
+            assert_eq!(square().edges().last(), Some((point(0, 2), point(0, 0))));
+        }
+
+        #[test]
+        fn areas() {
+            let square = square();
+            assert_eq!(square.twice_signed_area(), 8);
+            assert_eq!(square.orientation(), Some(Orientation::Counterclockwise));
+            assert_eq!(square.boundary_points(), 8);
+            assert_eq!(square.interior_points(), Some(1));
+            assert_eq!(square.lattice_points(), Some(9));
+
+            let reversed = Polygon::new(square.vertices().iter().rev().copied().collect());
+            assert_eq!(reversed.twice_signed_area(), -8);
+            assert_eq!(reversed.orientation(), Some(Orientation::Clockwise));
+            assert_eq!(reversed.lattice_points(), Some(9));
+
+            let triangle = Polygon::new(vec![point(0, 0), point(4, 0), point(0, 3)]);
+            assert_eq!(triangle.twice_signed_area(), 12);
+            assert_eq!((triangle.boundary_points(), triangle.interior_points()), (8, Some(3)));
+
+            assert_eq!(Polygon::new(vec![point(0, 0), point(3, 3)]).orientation(), None);
+        }
+
+        parameterized_test::create!{ degenerate, vertices, {
+            let polygon = Polygon::new(vertices);
+            assert_eq!(polygon.orientation(), None);
+            assert_eq!(polygon.interior_points(), None);
+            assert_eq!(polygon.lattice_points(), None);
+        }}
+        degenerate! {
+            single: vec![point(1, 1)],
+            segment: vec![point(0, 0), point(3, 0)],
+            collinear: vec![point(0, 0), point(2, 2), point(5, 5)],
+            retraced: vec![point(0, 0), point(0, 4), point(0, 2)],
+        }
+
+        #[test]
+        fn large() {
+            let moves = [vector(1_i64, 0), vector(0, 1), vector(-1, 0), vector(0, -1)].map(|v| v * 1000000);
+            let polygon = Polygon::from_moves(Point::ORIGIN, moves);
+            assert_eq!(polygon.lattice_points(), Some(1000001 * 1000001));
+        }
+
+        parameterized_test::create!{ containment, (pos, winding, contains), {
+            let square = square();
+            assert_eq!(square.contains(pos), contains);
+            if !square.on_boundary(pos) {
+                assert_eq!(square.winding_number(pos), winding);
+            }
+        }}
+        containment! {
+            inside: (point(1, 1), 1, true),
+            corner: (point(2, 2), 0, true),
+            edge: (point(0, 1), 0, true),
+            above: (point(1, -1), 0, false),
+            beside: (point(3, 1), 0, false),
+            in_line: (point(3, 0), 0, false),
+        }
+
+        #[test]
+        fn winding() {
+            // Loops around (1, 1) twice, clockwise
+            let twice = Polygon::new(vec![
+                point(0, 0), point(0, 2), point(2, 2), point(2, 0),
+                point(0, 0), point(0, 3), point(3, 3), point(3, 0)]);
+            assert_eq!(twice.winding_number(point(1, 1)), -2);
+            assert_eq!(twice.winding_number(point(1, 5)), 0);
+        }
+    }
+}
+pub use self::polygon::{Orientation, Polygon};
+
+mod segment {
+    use super::*;
+    use std::fmt;
+
+    // The straight line between two points, inclusive of both ends
+    #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    pub struct Segment<T = i32> {
+        pub start: Point<T>,
+        pub end: Point<T>,
+    }
+
+    #[inline]
+    pub const fn segment<T>(start: Point<T>, end: Point<T>) -> Segment<T> {
+        Segment { start, end }
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum Intersection<T = i32> {
+        Point(Point<T>),
+        // The segments are collinear and share more than one point
+        Overlap(Segment<T>),
+        // The segments cross, but not at a lattice point
+        OffLattice,
+    }
+
+    impl<T: Coordinate> Segment<T> {
+        pub fn vector(&self) -> Vector<T> { self.end - self.start }
+
+        pub fn len(&self) -> f64 { self.vector().len() }
+
+        pub fn grid_len(&self) -> T { self.vector().grid_len() }
+
+        pub fn is_axis_aligned(&self) -> bool {
+            self.start.x == self.end.x || self.start.y == self.end.y
+        }
+
+        pub fn is_diagonal(&self) -> bool {
+            let v = self.vector().abs();
+            v.x == v.y
+        }
+
+        pub fn bounds(&self) -> Bounds<T> {
+            Bounds::from_points(&[self.start, self.end]).expect("Non-empty")
+        }
+
+        pub fn contains(&self, pos: Point<T>) -> bool {
+            self.vector().cross(pos - self.start).is_zero() && self.bounds().contains(pos)
+        }
+
+        // The points closest to the segment, from start to end. For axis-aligned and diagonal
+        // segments these are exactly the lattice points on the segment.
+        // https://en.wikipedia.org/wiki/Bresenham%27s_line_algorithm#All_cases
+        pub fn points(&self) -> impl Iterator<Item = Point<T>> {
+            let (end, delta) = (self.end, self.vector());
+            let (dx, dy) = (delta.x.abs(), -delta.y.abs());
+            let step = delta.signum_unchecked();
+            let mut err = dx + dy;
+            let mut next = Some(self.start);
+            std::iter::from_fn(move || {
+                let pos = next?;
+                next = if pos == end { None } else {
+                    let mut following = pos;
+                    let e2 = err + err;
+                    if e2 >= dy { err = err + dy; following.x = following.x + step.x; }
+                    if e2 <= dx { err = err + dx; following.y = following.y + step.y; }
+                    Some(following)
+                };
+                Some(pos)
+            })
+        }
+
+        // Note the computation multiplies coordinates together, so large segments may overflow
+        // where a wider coordinate type would not.
+        pub fn intersection(&self, other: &Segment<T>) -> Option<Intersection<T>> {
+            let (d1, d2) = (self.vector(), other.vector());
+            let offset = other.start - self.start;
+            let denom = d1.cross(d2);
+            if denom.is_zero() {
+                // Parallel, so they only meet if they're on the same line
+                if !offset.cross(d1).is_zero() || !offset.cross(d2).is_zero() { return None; }
+                // Collinear points are ordered the same way along the line as they are by Ord
+                let lo = std::cmp::max(std::cmp::min(self.start, self.end), std::cmp::min(other.start, other.end));
+                let hi = std::cmp::min(std::cmp::max(self.start, self.end), std::cmp::max(other.start, other.end));
+                return match lo.cmp(&hi) {
+                    std::cmp::Ordering::Less => Some(Intersection::Overlap(segment(lo, hi))),
+                    std::cmp::Ordering::Equal => Some(Intersection::Point(lo)),
+                    std::cmp::Ordering::Greater => None,
+                };
+            }
+            // The intersection is at self.start + d1 * t / denom and other.start + d2 * u / denom
+            let (t, u) = (offset.cross(d2), offset.cross(d1));
+            let within = |n: T| if denom.is_positive() { n >= T::ZERO && n <= denom } else { n <= T::ZERO && n >= denom };
+            if !within(t) || !within(u) { return None; }
+            let (x, y) = (d1.x * t, d1.y * t);
+            if !(x % denom).is_zero() || !(y % denom).is_zero() { return Some(Intersection::OffLattice); }
+            Some(Intersection::Point(self.start + vector(x / denom, y / denom)))
+        }
+
+        // The part of the segment within bounds, trimmed to the first and last lattice points on
+        // the segment that are in bounds.
+        pub fn clip(&self, bounds: Bounds<T>) -> Option<Segment<T>> {
+            // The segment's lattice points are start + step * k, for k in 0..=steps
+            let delta = self.vector();
+            let steps = delta.x.gcd(&delta.y);
+            if steps.is_zero() {
+                return if bounds.contains(self.start) { Some(*self) } else { None };
+            }
+            let step = vector(delta.x / steps, delta.y / steps);
+            let (mut lo, mut hi) = (T::ZERO, steps);
+            for (start, step, min, max) in [
+                    (self.start.x, step.x, bounds.min.x, bounds.max.x),
+                    (self.start.y, step.y, bounds.min.y, bounds.max.y)] {
+                if step.is_zero() {
+                    if start < min || start > max { return None; }
+                    continue;
+                }
+                let (a, b) = ((min - start).div_ceil(&step), (max - start).div_floor(&step));
+                let (a, b) = if step.is_positive() { (a, b) } else { ((max - start).div_ceil(&step), (min - start).div_floor(&step)) };
+                lo = std::cmp::max(lo, a);
+                hi = std::cmp::min(hi, b);
+            }
+            if lo > hi { return None; }
+            Some(segment(self.start + step * lo, self.start + step * hi))
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for Segment<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?} -> {:?}", self.start, self.end)
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Display for Segment<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use assert_approx_eq::assert_approx_eq;
+
+        #[test]
+        fn properties() {
+            let s = segment(point(1, 1), point(4, 5));
+            assert_eq!(s.vector(), vector(3, 4));
+            assert_approx_eq!(s.len(), 5_f64, f64::EPSILON);
+            assert_eq!(s.grid_len(), 7);
+            assert!(!s.is_axis_aligned());
+            assert!(!s.is_diagonal());
+            assert!(segment(point(1, 1), point(1, -5)).is_axis_aligned());
+            assert!(segment(point(1, 1), point(-2, 4)).is_diagonal());
+            assert_eq!(s.to_string(), "(1, 1) -> (4, 5)");
+        }
+
+        parameterized_test::create!{ rasterize, (s, expected), {
+            let points: Vec<_> = s.points().collect();
+            assert_eq!(points, expected);
+            assert_eq!(segment(s.end, s.start).points().collect::<Vec<_>>(),
+                       expected.iter().rev().copied().collect::<Vec<_>>());
+        }}
+        rasterize! {
+            single: (segment(point(2, 2), point(2, 2)), [point(2, 2)]),
+            horizontal: (segment(point(0, 0), point(3, 0)), [point(0, 0), point(1, 0), point(2, 0), point(3, 0)]),
+            vertical: (segment(point(0, 1), point(0, -1)), [point(0, 1), point(0, 0), point(0, -1)]),
+            diagonal: (segment(point(0, 0), point(-2, 2)), [point(0, 0), point(-1, 1), point(-2, 2)]),
+            shallow: (segment(point(0, 0), point(5, 2)), [point(0, 0), point(1, 0), point(2, 1), point(3, 1), point(4, 2), point(5, 2)]),
+        }
+
+        #[test]
+        fn contains() {
+            let s = segment(point(0, 0), point(4, 2));
+            assert!(s.contains(point(2, 1)));
+            assert!(s.contains(point(4, 2)));
+            assert!(!s.contains(point(1, 0)));
+            assert!(!s.contains(point(6, 3)));
+        }
+
+        parameterized_test::create!{ intersections, (a, b, expected), {
+            assert_eq!(a.intersection(&b), expected);
+            assert_eq!(b.intersection(&a), expected);
+        }}
+        intersections! {
+            cross: (segment(point(0, 0), point(4, 4)), segment(point(0, 4), point(4, 0)), Some(Intersection::Point(point(2, 2)))),
+            off_lattice: (segment(point(0, 0), point(1, 1)), segment(point(0, 1), point(1, 0)), Some(Intersection::OffLattice)),
+            touch: (segment(point(0, 0), point(2, 0)), segment(point(2, 0), point(2, 5)), Some(Intersection::Point(point(2, 0)))),
+            miss: (segment(point(0, 0), point(2, 0)), segment(point(3, -1), point(3, 1)), None),
+            parallel: (segment(point(0, 0), point(2, 0)), segment(point(0, 1), point(2, 1)), None),
+            overlap: (segment(point(0, 0), point(4, 2)), segment(point(6, 3), point(2, 1)), Some(Intersection::Overlap(segment(point(2, 1), point(4, 2))))),
+            end_to_end: (segment(point(0, 0), point(2, 0)), segment(point(2, 0), point(5, 0)), Some(Intersection::Point(point(2, 0)))),
+            collinear_gap: (segment(point(0, 0), point(2, 0)), segment(point(3, 0), point(5, 0)), None),
+            degenerate: (segment(point(1, 1), point(1, 1)), segment(point(0, 0), point(2, 2)), Some(Intersection::Point(point(1, 1)))),
+        }
+
+        parameterized_test::create!{ clips, (s, expected), {
+            let b = bounds(point(0, 0), point(4, 4));
+            assert_eq!(s.clip(b), expected);
+            let reversed = segment(s.end, s.start);
+            assert_eq!(reversed.clip(b), expected.map(|e: Segment| segment(e.end, e.start)));
+        }}
+        clips! {
+            inside: (segment(point(1, 1), point(3, 2)), Some(segment(point(1, 1), point(3, 2)))),
+            horizontal: (segment(point(-3, 2), point(7, 2)), Some(segment(point(0, 2), point(4, 2)))),
+            diagonal: (segment(point(-2, 6), point(6, -2)), Some(segment(point(0, 4), point(4, 0)))),
+            steep: (segment(point(-1, -3), point(2, 6)), Some(segment(point(0, 0), point(1, 3)))),
+            outside: (segment(point(5, 0), point(5, 4)), None),
+            corner: (segment(point(3, 6), point(6, 3)), None),
+            single: (segment(point(2, 2), point(2, 2)), Some(segment(point(2, 2), point(2, 2)))),
+        }
+    }
+}
+pub use self::segment::{Intersection, Segment, segment};
+
+mod spatial_index {
+    use super::*;
+    use std::collections::HashMap;
+    use itertools::Either;
+
+    // Buckets values by the square cells of the plane they cover, so queries only need to consider
+    // values near the area being queried rather than every value. Values can cover a single Point
+    // or any Bounds; cell_size should be chosen so that most queries only touch a few cells.
+    #[derive(Clone, Debug)]
+    pub struct SpatialIndex<V> {
+        cell_size: i32,
+        entries: Vec<(Bounds, V)>,
+        cells: HashMap<Point, Vec<usize>>,
+        // The bounds of all occupied cells, which limits how far nearest() needs to search
+        occupied: Option<Bounds>,
+    }
+
+    impl<V> SpatialIndex<V> {
+        pub fn new(cell_size: i32) -> SpatialIndex<V> {
+            assert!(cell_size > 0);
+            SpatialIndex{ cell_size, entries: Vec::new(), cells: HashMap::new(), occupied: None }
+        }
+
+        pub fn len(&self) -> usize { self.entries.len() }
+
+        pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+        fn cell(&self, pos: Point) -> Point {
+            point(pos.x.div_euclid(self.cell_size), pos.y.div_euclid(self.cell_size))
+        }
+
+        fn cells_covering(&self, area: Bounds) -> Bounds {
+            bounds(self.cell(area.min), self.cell(area.max))
+        }
+
+        pub fn insert(&mut self, area: Bounds, value: V) {
+            let index = self.entries.len();
+            self.entries.push((area, value));
+            let cells = self.cells_covering(area);
+            for cell in cells.iter() {
+                self.cells.entry(cell).or_default().push(index);
+            }
+            self.occupied = Some(match self.occupied {
+                Some(occupied) => Bounds::from_points(&[occupied.min, occupied.max, cells.min, cells.max]).expect("Non-empty"),
+                None => cells,
+            });
+        }
+
+        pub fn insert_point(&mut self, pos: Point, value: V) {
+            self.insert(bounds(pos, pos), value);
+        }
+
+        // All entries, in insertion order
+        pub fn iter(&self) -> impl Iterator<Item = (Bounds, &V)> + '_ {
+            self.entries.iter().map(|(b, v)| (*b, v))
+        }
+
+        // Indices of entries that might intersect area, possibly with duplicates
+        fn candidates(&self, area: Bounds) -> impl Iterator<Item = usize> + '_ {
+            let cells = self.cells_covering(area);
+            // Large areas are cheaper to check against the occupied cells than cell-by-cell
+            if cells.area() as usize > self.cells.len() {
+                Either::Left(self.cells.iter().filter(move |(c, _)| cells.contains(**c)).flat_map(|(_, v)| v).copied())
+            } else {
+                Either::Right(cells.iter().filter_map(|c| self.cells.get(&c)).flatten().copied())
+            }
+        }
+
+        // The entries that intersect area, in insertion order
+        pub fn query(&self, area: Bounds) -> impl Iterator<Item = (Bounds, &V)> + '_ {
+            let mut indices: Vec<_> = self.candidates(area).filter(|&i| self.entries[i].0.intersects(area)).collect();
+            indices.sort_unstable();
+            indices.dedup();
+            indices.into_iter().map(|i| { let (b, v) = &self.entries[i]; (*b, v) })
+        }
+
+        pub fn any_within(&self, area: Bounds) -> bool {
+            self.candidates(area).any(|i| self.entries[i].0.intersects(area))
+        }
+
+        // The entry closest to pos by grid distance, where entries containing pos have distance 0.
+        // Ties are broken by insertion order.
+        pub fn nearest(&self, pos: Point) -> Option<(Bounds, &V)> {
+            let occupied = self.occupied?;
+            let center = self.cell(pos);
+            let max_ring = [occupied.min, occupied.max].iter()
+                .map(|c| { let d = (*c - center).abs(); std::cmp::max(d.x, d.y) })
+                .max().expect("Non-empty");
+            let mut best: Option<(i32, usize)> = None;
+            for ring in 0..=max_ring {
+                for cell in ring_cells(center, ring) {
+                    for &i in self.cells.get(&cell).into_iter().flatten() {
+                        let candidate = (distance(self.entries[i].0, pos), i);
+                        if best.is_none_or(|b| candidate < b) { best = Some(candidate); }
+                    }
+                }
+                // Entries not seen yet are all in cells outside this ring, and so are further than
+                // ring * cell_size away
+                if best.is_some_and(|(d, _)| d <= ring * self.cell_size) { break; }
+            }
+            best.map(|(_, i)| { let (b, v) = &self.entries[i]; (*b, v) })
+        }
+    }
+
+    // The grid distance from pos to the closest point in area
+    fn distance(area: Bounds, pos: Point) -> i32 {
+        let dx = std::cmp::max(0, std::cmp::max(area.min.x - pos.x, pos.x - area.max.x));
+        let dy = std::cmp::max(0, std::cmp::max(area.min.y - pos.y, pos.y - area.max.y));
+        dx + dy
+    }
+
+    // The cells exactly ring cells away from center, in either axis
+    fn ring_cells(center: Point, ring: i32) -> impl Iterator<Item = Point> {
+        let rows = (-ring..=ring).flat_map(move |x| [vector(x, -ring), vector(x, ring)]);
+        let sides = (-ring+1..ring).flat_map(move |y| [vector(-ring, y), vector(ring, y)]);
+        // When ring is 0 the top and bottom rows are the same, single, cell
+        rows.chain(sides).take(std::cmp::max(8 * ring, 1) as usize).map(move |v| center + v)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashSet;
+
+        fn example() -> SpatialIndex<char> {
+            let mut index = SpatialIndex::new(3);
+            index.insert_point(point(0, 0), 'a');
+            index.insert_point(point(5, 1), 'b');
+            index.insert(bounds(point(-4, 2), point(4, 2)), 'c');
+            index.insert_point(point(-20, -20), 'd');
+            index
+        }
+
+        #[test]
+        fn ring() {
+            assert_eq!(ring_cells(point(1, 1), 0).collect::<Vec<_>>(), [point(1, 1)]);
+            let ring: HashSet<_> = ring_cells(point(1, 1), 2).collect();
+            assert_eq!(ring.len(), 16);
+            assert!(ring.iter().all(|p| { let d = (*p - point(1, 1)).abs(); std::cmp::max(d.x, d.y) == 2 }));
+        }
+
+        parameterized_test::create!{ queries, (area, expected), {
+            let index = example();
+            let found: String = index.query(area).map(|(_, c)| c).collect();
+            assert_eq!(found, expected);
+            assert_eq!(index.any_within(area), !expected.is_empty());
+        }}
+        queries! {
+            origin: (bounds(point(0, 0), point(0, 0)), "a"),
+            nothing: (bounds(point(1, 0), point(4, 1)), ""),
+            spanning: (bounds(point(-1, -1), point(5, 5)), "abc"),
+            edge: (bounds(point(-4, 2), point(-4, 2)), "c"),
+            everything: (bounds(point(-100, -100), point(100, 100)), "abcd"),
+        }
+
+        parameterized_test::create!{ nearest, (pos, expected), {
+            let index = example();
+            assert_eq!(index.nearest(pos).map(|(_, c)| *c), Some(expected));
+        }}
+        nearest! {
+            exact: (point(0, 0), 'a'),
+            tie: (point(0, 1), 'a'),
+            inside: (point(2, 2), 'c'),
+            below: (point(-3, 10), 'c'),
+            right: (point(9, 1), 'b'),
+            far: (point(-15, -30), 'd'),
+        }
+
+        #[test]
+        fn empty() {
+            let index: SpatialIndex<()> = SpatialIndex::new(10);
+            assert!(index.is_empty());
+            assert_eq!(index.nearest(point(0, 0)), None);
+            assert!(!index.any_within(bounds(point(0, 0), point(10, 10))));
+        }
+
+        #[test]
+        fn matches_brute_force() {
+            let rng = fastrand::Rng::with_seed(22);
+            let mut index = SpatialIndex::new(4);
+            for i in 0..50 {
+                index.insert_point(point(rng.i32(-30..30), rng.i32(-30..30)), i);
+            }
+            for _ in 0..100 {
+                let pos = point(rng.i32(-40..40), rng.i32(-40..40));
+                let (found, _) = index.nearest(pos).unwrap();
+                let closest = index.iter().map(|(b, _)| distance(b, pos)).min().unwrap();
+                assert_eq!(distance(found, pos), closest, "{}", pos);
+
+                let area = bounds(pos, pos + vector(rng.i32(0..10), rng.i32(0..10)));
+                let expected: Vec<_> = index.iter().filter(|(b, _)| b.intersects(area)).map(|(_, v)| *v).collect();
+                assert_eq!(index.query(area).map(|(_, v)| *v).collect::<Vec<_>>(), expected);
+            }
+        }
+    }
+}
+pub use self::spatial_index::SpatialIndex;
+
+mod direction {
+    use super::*;
+    use std::fmt;
+    use std::str::FromStr;
+    use anyhow::{bail, Error, Result};
+
+    // Compass directions, where north is up as a map is drawn, i.e. towards negative y. Turns are
+    // compass turns, so right90() turns clockwise as the map is drawn. Note this is the opposite
+    // of Vector::right90(), which turns clockwise with y increasing upwards.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub enum Direction {
+        North,
+        NorthEast,
+        East,
+        SouthEast,
+        South,
+        SouthWest,
+        West,
+        NorthWest,
+    }
+
+    impl Direction {
+        // Clockwise, starting from North
+        pub const ALL: [Direction; 8] = [
+            Direction::North, Direction::NorthEast, Direction::East, Direction::SouthEast,
+            Direction::South, Direction::SouthWest, Direction::West, Direction::NorthWest];
+        pub const CARDINAL: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+        pub const DIAGONAL: [Direction; 4] = [Direction::NorthEast, Direction::SouthEast, Direction::SouthWest, Direction::NorthWest];
+
+        pub fn vector(&self) -> Vector {
+            match self {
+                Direction::North => vector(0, -1),
+                Direction::NorthEast => vector(1, -1),
+                Direction::East => vector(1, 0),
+                Direction::SouthEast => vector(1, 1),
+                Direction::South => vector(0, 1),
+                Direction::SouthWest => vector(-1, 1),
+                Direction::West => vector(-1, 0),
+                Direction::NorthWest => vector(-1, -1),
+            }
+        }
+
+        // The direction of a unit vector, or None for any other vector
+        pub fn from_vector(v: Vector) -> Option<Direction> {
+            Direction::ALL.iter().find(|d| d.vector() == v).copied()
+        }
+
+        // The numeric encoding where 0 is East and each step turns clockwise, i.e. 0-3 are E, S,
+        // W, N. Returns None for any other digit.
+        pub fn from_digit(digit: u32) -> Option<Direction> {
+            Some(match digit {
+                0 => Direction::East,
+                1 => Direction::South,
+                2 => Direction::West,
+                3 => Direction::North,
+                _ => return None,
+            })
+        }
+
+        pub fn is_cardinal(&self) -> bool {
+            matches!(self, Direction::North | Direction::East | Direction::South | Direction::West)
+        }
+
+        // Turns clockwise by an eighth of a turn for each step, or counterclockwise if negative
+        fn turn(&self, steps: i32) -> Direction {
+            Direction::ALL[(*self as i32 + steps).rem_euclid(8) as usize]
+        }
+
+        pub fn right45(&self) -> Direction { self.turn(1) }
+
+        pub fn left45(&self) -> Direction { self.turn(-1) }
+
+        pub fn right90(&self) -> Direction { self.turn(2) }
+
+        pub fn left90(&self) -> Direction { self.turn(-2) }
+
+        pub fn opposite(&self) -> Direction { self.turn(4) }
+
+        // The abbreviation, e.g. N or NE
+        pub fn abbreviation(&self) -> &'static str {
+            match self {
+                Direction::North => "N",
+                Direction::NorthEast => "NE",
+                Direction::East => "E",
+                Direction::SouthEast => "SE",
+                Direction::South => "S",
+                Direction::SouthWest => "SW",
+                Direction::West => "W",
+                Direction::NorthWest => "NW",
+            }
+        }
+    }
+
+    impl From<Direction> for Vector {
+        fn from(dir: Direction) -> Vector { dir.vector() }
+    }
+
+    impl TryFrom<Vector> for Direction {
+        type Error = Error;
+
+        fn try_from(v: Vector) -> Result<Direction> {
+            match Direction::from_vector(v) {
+                Some(dir) => Ok(dir),
+                None => bail!("Not a direction: {}", v),
+            }
+        }
+    }
+
+    // Accepts the common single-character encodings: compass points (N), relative moves (U), and
+    // arrows (^).
+    impl TryFrom<char> for Direction {
+        type Error = Error;
+
+        fn try_from(c: char) -> Result<Direction> {
+            Ok(match c {
+                'N' | 'U' | '^' => Direction::North,
+                'E' | 'R' | '>' => Direction::East,
+                'S' | 'D' | 'v' => Direction::South,
+                'W' | 'L' | '<' => Direction::West,
+                _ => bail!("Invalid direction: {:?}", c),
+            })
+        }
+    }
+
+    // Accepts any single-character encoding, or an abbreviation such as NE.
+    impl FromStr for Direction {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Direction> {
+            if let Some(dir) = Direction::ALL.iter().find(|d| d.abbreviation() == s) {
+                return Ok(*dir);
+            }
+            let mut chars = s.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c.try_into(),
+                _ => bail!("Invalid direction: {}", s),
+            }
+        }
+    }
+
+    impl fmt::Display for Direction {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.abbreviation())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashSet;
+
+        #[test]
+        fn vectors() {
+            for dir in Direction::ALL {
+                assert_eq!(Direction::from_vector(dir.vector()), Some(dir));
+                assert_eq!(Vector::from(dir).grid_len(), if dir.is_cardinal() { 1 } else { 2 });
+            }
+            assert_eq!(Direction::CARDINAL.iter().map(|d| d.vector()).collect::<HashSet<_>>(),
+                       Vector::CARDINAL.iter().copied().collect());
+            assert_eq!(Direction::ALL.iter().map(|d| d.vector()).collect::<HashSet<_>>(),
+                       Vector::ORDINAL.iter().copied().collect());
+            assert!(Direction::try_from(vector(2, 0)).is_err());
+            assert!(Direction::try_from(Vector::ZERO).is_err());
+        }
+
+        #[test]
+        fn turns() {
+            assert_eq!(Direction::North.right90(), Direction::East);
+            assert_eq!(Direction::North.left90(), Direction::West);
+            assert_eq!(Direction::West.right45(), Direction::NorthWest);
+            assert_eq!(Direction::NorthWest.right45(), Direction::North);
+            assert_eq!(Direction::North.left45(), Direction::NorthWest);
+            for dir in Direction::ALL {
+                assert_eq!(dir.opposite().vector(), vector(-dir.vector().x, -dir.vector().y));
+                assert_eq!(dir.right90().right90(), dir.opposite());
+                assert_eq!(dir.left90().right90(), dir);
+                // Compass turns are the reverse of the y-up turns
+                assert_eq!(dir.right90().vector(), dir.vector().left90());
+            }
+        }
+
+        parameterized_test::create!{ parse, (s, expected), {
+            assert_eq!(s.parse::<Direction>().unwrap(), expected);
+        }}
+        parse! {
+            compass: ("N", Direction::North),
+            relative: ("R", Direction::East),
+            arrow: ("v", Direction::South),
+            left: ("<", Direction::West),
+            ordinal: ("SW", Direction::SouthWest),
+        }
+
+        #[test]
+        fn invalid() {
+            assert!("X".parse::<Direction>().is_err());
+            assert!("UU".parse::<Direction>().is_err());
+            assert!("".parse::<Direction>().is_err());
+            assert_eq!(Direction::SouthWest.to_string(), "SW");
+        }
+
+        #[test]
+        fn digits() {
+            let dirs: Vec<_> = (0..4).map(|d| Direction::from_digit(d).unwrap()).collect();
+            assert_eq!(dirs, [Direction::East, Direction::South, Direction::West, Direction::North]);
+            assert_eq!(Direction::from_digit(4), None);
+        }
+    }
+}
+pub use self::direction::Direction;
+
+mod axes {
+    use super::*;
+    use std::ops::RangeInclusive;
+    use itertools::Either;
+
+    // Which way the y axis points. Puzzle maps are usually drawn with y increasing downwards, like
+    // a screen, but puzzles given in Cartesian coordinates expect y to increase upwards. Turning
+    // and drawing depend on which way y points, so those that care take the axes into account.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum Axes {
+        // y increases downwards, so the first row drawn is the one with the smallest y
+        Screen,
+        // y increases upwards, so the first row drawn is the one with the largest y
+        Cartesian,
+    }
+
+    impl Axes {
+        // Converts points, vectors, or bounds from these axes to the other axes. Converting between
+        // screen and Cartesian coordinates negates y.
+        pub fn to(&self, other: Axes) -> Transform {
+            if *self == other { Transform::Identity } else { Transform::FlipVertical }
+        }
+
+        // Turns v a quarter-turn clockwise, as drawn
+        pub fn right90<T: Coordinate>(&self, v: Vector<T>) -> Vector<T> {
+            match self {
+                Axes::Screen => v.left90(),
+                Axes::Cartesian => v.right90(),
+            }
+        }
+
+        // Turns v a quarter-turn counterclockwise, as drawn
+        pub fn left90<T: Coordinate>(&self, v: Vector<T>) -> Vector<T> {
+            match self {
+                Axes::Screen => v.right90(),
+                Axes::Cartesian => v.left90(),
+            }
+        }
+
+        // The vector pointing in the given compass direction, where north is up as drawn
+        pub fn vector(&self, dir: Direction) -> Vector {
+            Axes::Screen.to(*self).vector(dir.vector())
+        }
+
+        // The rows of bounds in the order they're drawn, each from left to right
+        pub fn rows<T: Coordinate>(&self, bounds: Bounds<T>) -> impl Iterator<Item = impl Iterator<Item = Point<T>>>
+                where RangeInclusive<T>: DoubleEndedIterator<Item = T> {
+            let ys = match self {
+                Axes::Screen => Either::Left(bounds.min.y..=bounds.max.y),
+                Axes::Cartesian => Either::Right((bounds.min.y..=bounds.max.y).rev()),
+            };
+            ys.map(move |y| (bounds.min.x..=bounds.max.x).map(move |x| point(x, y)))
+        }
+
+        // Draws each point in bounds as a character, one line per row and without a trailing
+        // newline.
+        pub fn render<T: Coordinate>(&self, bounds: Bounds<T>, mut f: impl FnMut(Point<T>) -> char) -> String
+                where RangeInclusive<T>: DoubleEndedIterator<Item = T> {
+            let mut out = String::new();
+            for (i, row) in self.rows(bounds).enumerate() {
+                if i > 0 { out.push('\n'); }
+                out.extend(row.map(&mut f));
+            }
+            out
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn conversion() {
+            assert_eq!(Axes::Screen.to(Axes::Screen).point(point(1, 2)), point(1, 2));
+            assert_eq!(Axes::Cartesian.to(Axes::Screen).point(point(1, 2)), point(1, -2));
+            assert_eq!(Axes::Screen.to(Axes::Cartesian).point(point(1_i64, -2)), point(1, 2));
+            assert_eq!(Axes::Cartesian.to(Axes::Screen).bounds(bounds(point(0, 0), point(3, 3))), bounds(point(0, -3), point(3, 0)));
+        }
+
+        #[test]
+        fn turns() {
+            for axes in [Axes::Screen, Axes::Cartesian] {
+                for dir in Direction::ALL {
+                    assert_eq!(axes.right90(axes.vector(dir)), axes.vector(dir.right90()), "{:?} {}", axes, dir);
+                    assert_eq!(axes.left90(axes.vector(dir)), axes.vector(dir.left90()), "{:?} {}", axes, dir);
+                }
+            }
+            assert_eq!(Axes::Screen.vector(Direction::North), vector(0, -1));
+            assert_eq!(Axes::Cartesian.vector(Direction::North), vector(0, 1));
+            assert_eq!(Axes::Cartesian.right90(vector(0, 1)), vector(1, 0));
+        }
+
+        #[test]
+        fn render() {
+            let b = bounds(point(0, 0), point(2, 1));
+            let draw = |p: Point| if p == point(0, 0) { '#' } else { '.' };
+            assert_eq!(Axes::Screen.render(b, draw), "#..\n...");
+            assert_eq!(Axes::Cartesian.render(b, draw), "...\n#..");
+            let first_rows: Vec<_> = [Axes::Screen, Axes::Cartesian].iter()
+                .map(|a| a.rows(b).next().unwrap().collect::<Vec<_>>()).collect();
+            assert_eq!(first_rows, [[point(0, 0), point(1, 0), point(2, 0)], [point(0, 1), point(1, 1), point(2, 1)]]);
+        }
+    }
+}
+pub use self::axes::Axes;
+
+mod hex {
+    use super::*;
+    use std::fmt;
+    use std::ops::{Add,AddAssign,Mul,Sub};
+    use anyhow::{bail, Context, Result};
+
+    // A position on a hex grid in axial coordinates, see https://www.redblobgames.com/grids/hexagons/
+    // The third cube coordinate is implied by q + r + s = 0. Like Point and Vector combined, since
+    // offsets between hexes are themselves axial coordinates; q increases to the east and r to the
+    // south, as drawn.
+    #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    pub struct Axial<T = i32> {
+        pub q: T,
+        pub r: T,
+    }
+
+    #[inline]
+    pub const fn axial<T>(q: T, r: T) -> Axial<T> {
+        Axial { q, r }
+    }
+
+    // A position on a hex grid in cube coordinates, where q + r + s = 0. Easier to reason about
+    // than axial coordinates, e.g. rotations and distances treat all three axes alike.
+    #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    pub struct Cube<T = i32> {
+        pub q: T,
+        pub r: T,
+        pub s: T,
+    }
+
+    impl Axial {
+        // The offsets to each adjacent hex, in counterclockwise order as drawn
+        pub const NEIGHBORS: [Axial; 6] = [
+            axial(1, 0), axial(1, -1), axial(0, -1), axial(-1, 0), axial(-1, 1), axial(0, 1)];
+    }
+
+    impl<T: Coordinate> Axial<T> {
+        pub const ORIGIN: Axial<T> = axial(T::ZERO, T::ZERO);
+
+        pub fn neighbors(&self) -> [Axial<T>; 6] {
+            Axial::NEIGHBORS.map(|v| *self + axial(T::from(v.q).expect("Fits"), T::from(v.r).expect("Fits")))
+        }
+
+        // The number of steps between this hex and the origin
+        pub fn len(&self) -> T {
+            Cube::from(*self).len()
+        }
+
+        // The number of steps between two hexes
+        pub fn distance(&self, other: &Axial<T>) -> T {
+            (*other - *self).len()
+        }
+    }
+
+    impl<T: Coordinate> Cube<T> {
+        pub fn new(q: T, r: T, s: T) -> Cube<T> {
+            assert_eq!(q + r + s, T::ZERO, "Invalid cube coordinates");
+            Cube { q, r, s }
+        }
+
+        pub fn len(&self) -> T {
+            self.q.abs().max(self.r.abs()).max(self.s.abs())
+        }
+
+        pub fn distance(&self, other: &Cube<T>) -> T {
+            (*other - *self).len()
+        }
+    }
+
+    impl<T: Coordinate> From<Axial<T>> for Cube<T> {
+        fn from(hex: Axial<T>) -> Cube<T> {
+            Cube { q: hex.q, r: hex.r, s: -hex.q - hex.r }
+        }
+    }
+
+    impl<T: Coordinate> From<Cube<T>> for Axial<T> {
+        fn from(cube: Cube<T>) -> Axial<T> {
+            axial(cube.q, cube.r)
+        }
+    }
+
+    impl<T: Coordinate> Add for Axial<T> {
+        type Output = Axial<T>;
+
+        fn add(self, other: Axial<T>) -> Axial<T> { axial(self.q + other.q, self.r + other.r) }
+    }
+
+    impl<T: Coordinate> AddAssign for Axial<T> {
+        fn add_assign(&mut self, other: Axial<T>) {
+            *self = *self + other;
+        }
+    }
+
+    impl<T: Coordinate> Sub for Axial<T> {
+        type Output = Axial<T>;
+
+        fn sub(self, other: Axial<T>) -> Axial<T> { axial(self.q - other.q, self.r - other.r) }
+    }
+
+    impl<T: Coordinate> Mul<T> for Axial<T> {
+        type Output = Axial<T>;
+
+        fn mul(self, n: T) -> Axial<T> { axial(self.q * n, self.r * n) }
+    }
+
+    impl<T: Coordinate> Add for Cube<T> {
+        type Output = Cube<T>;
+
+        fn add(self, other: Cube<T>) -> Cube<T> { Cube::new(self.q + other.q, self.r + other.r, self.s + other.s) }
+    }
+
+    impl<T: Coordinate> Sub for Cube<T> {
+        type Output = Cube<T>;
+
+        fn sub(self, other: Cube<T>) -> Cube<T> { Cube::new(self.q - other.q, self.r - other.r, self.s - other.s) }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for Axial<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "({:?}, {:?})", self.q, self.r)
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Display for Axial<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Debug for Cube<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "({:?}, {:?}, {:?})", self.q, self.r, self.s)
+        }
+    }
+
+    impl<T: fmt::Debug> fmt::Display for Cube<T> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    // How hexes are drawn, which determines which compass directions lead to a neighbor. Flat-topped
+    // hexes have neighbors to the north and south, and pointy-topped hexes have neighbors to the
+    // east and west; both have neighbors to the NE, SE, SW, and NW.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum HexLayout {
+        FlatTop,
+        PointyTop,
+    }
+
+    impl HexLayout {
+        // The offset to the neighbor in the given direction, or None if there's no neighbor that way
+        pub fn vector(&self, dir: Direction) -> Option<Axial> {
+            use Direction::*;
+            Some(match (self, dir) {
+                (HexLayout::FlatTop, North) => axial(0, -1),
+                (HexLayout::FlatTop, South) => axial(0, 1),
+                (HexLayout::FlatTop, SouthEast) => axial(1, 0),
+                (HexLayout::FlatTop, NorthWest) => axial(-1, 0),
+                (HexLayout::PointyTop, East) => axial(1, 0),
+                (HexLayout::PointyTop, West) => axial(-1, 0),
+                (HexLayout::PointyTop, SouthEast) => axial(0, 1),
+                (HexLayout::PointyTop, NorthWest) => axial(0, -1),
+                (_, NorthEast) => axial(1, -1),
+                (_, SouthWest) => axial(-1, 1),
+                _ => return None,
+            })
+        }
+
+        // Parses a direction such as ne or SW into the offset to that neighbor
+        pub fn parse_direction(&self, s: &str) -> Result<Axial> {
+            let dir = Direction::ALL.iter().find(|d| d.abbreviation().eq_ignore_ascii_case(s))
+                .with_context(|| format!("Invalid direction: {}", s))?;
+            self.vector(*dir).with_context(|| format!("No {:?} neighbor in direction {}", self, s))
+        }
+
+        // Parses a sequence of directions, either comma-separated (ne,ne,s) or concatenated (nwwswee)
+        pub fn parse_path(&self, s: &str) -> Result<Vec<Axial>> {
+            let s = s.trim();
+            let mut ret = Vec::new();
+            let mut i = 0;
+            while i < s.len() {
+                if s[i..].starts_with(',') { i += 1; continue; }
+                // Two-character directions are never a valid one-character direction followed by
+                // another, so they can be matched greedily.
+                let two = s.get(i..i+2).and_then(|d| self.parse_direction(d).ok());
+                match two {
+                    Some(v) => { ret.push(v); i += 2; },
+                    None => match s.get(i..i+1) {
+                        Some(d) => { ret.push(self.parse_direction(d)?); i += 1; },
+                        None => bail!("Invalid path: {}", s),
+                    },
+                }
+            }
+            Ok(ret)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashSet;
+
+        #[test]
+        fn conversion() {
+            let hex = axial(2, -5);
+            let cube = Cube::from(hex);
+            assert_eq!(cube, Cube::new(2, -5, 3));
+            assert_eq!(Axial::from(cube), hex);
+            assert_eq!(cube.to_string(), "(2, -5, 3)");
+        }
+
+        #[test]
+        #[should_panic]
+        fn invalid_cube() {
+            Cube::new(1, 1, 1);
+        }
+
+        #[test]
+        fn neighbors() {
+            let hex = axial(3_i64, 4);
+            for n in hex.neighbors() {
+                assert_eq!(hex.distance(&n), 1);
+                assert_eq!(Cube::from(hex).distance(&Cube::from(n)), 1);
+            }
+            assert_eq!(hex.neighbors().iter().collect::<HashSet<_>>().len(), 6);
+            for layout in [HexLayout::FlatTop, HexLayout::PointyTop] {
+                let vectors: HashSet<_> = Direction::ALL.iter().filter_map(|&d| layout.vector(d)).collect();
+                assert_eq!(vectors, Axial::NEIGHBORS.iter().copied().collect(), "{:?}", layout);
+                for dir in Direction::ALL {
+                    if let Some(v) = layout.vector(dir) {
+                        assert_eq!(layout.vector(dir.opposite()), Some(axial(-v.q, -v.r)));
+                    }
+                }
+            }
+        }
+
+        parameterized_test::create!{ distance, (a, b, expected), {
+            assert_eq!(a.distance(&b), expected);
+            assert_eq!(b.distance(&a), expected);
+        }}
+        distance! {
+            same: (axial(1, 1), axial(1, 1), 0),
+            straight: (Axial::ORIGIN, axial(3, 0), 3),
+            diagonal: (Axial::ORIGIN, axial(3, -3), 3),
+            mixed: (axial(-1, 2), axial(2, 0), 3),
+            bent: (Axial::ORIGIN, axial(2, 1), 3),
+        }
+
+        parameterized_test::create!{ paths, (layout, path, expected), {
+            let end = layout.parse_path(path).unwrap().into_iter().fold(Axial::ORIGIN, |p, v| p + v);
+            assert_eq!(end.len(), expected);
+        }}
+        paths! {
+            // From 2017 Day 11
+            flat_away: (HexLayout::FlatTop, "ne,ne,ne", 3),
+            flat_back: (HexLayout::FlatTop, "ne,ne,sw,sw", 0),
+            flat_bent: (HexLayout::FlatTop, "ne,ne,s,s", 2),
+            flat_turn: (HexLayout::FlatTop, "se,sw,se,sw,sw", 3),
+            // From 2020 Day 24
+            pointy_loop: (HexLayout::PointyTop, "nwwswee", 0),
+            pointy_step: (HexLayout::PointyTop, "esew", 1),
+        }
+
+        #[test]
+        fn invalid() {
+            assert!(HexLayout::FlatTop.parse_direction("e").is_err());
+            assert!(HexLayout::PointyTop.parse_direction("n").is_err());
+            assert!(HexLayout::PointyTop.parse_direction("x").is_err());
+            assert_eq!(HexLayout::PointyTop.parse_direction("NE").unwrap(), axial(1, -1));
+            assert!(HexLayout::PointyTop.parse_path("nwx").is_err());
+            assert!(HexLayout::FlatTop.parse_path("nee").is_err());
+        }
+    }
+}
+pub use self::hex::{Axial, Cube, HexLayout, axial};
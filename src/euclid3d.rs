@@ -3,12 +3,14 @@
 //   https://crates.io/crates/euclid - https://doc.servo.org/src/euclid/point.rs.html
 
 use std::fmt;
-use std::ops::{Add,AddAssign,Sub};
+use std::ops::{Add,AddAssign,Div,Mul,Neg,Sub};
 use std::str::FromStr;
 use std::cmp;
 use anyhow::{Context, Error, Result};
 use lazy_regex::regex_captures;
 
+use crate::euclid;
+
 mod point {
     use super::*;
 
@@ -174,6 +176,100 @@ mod bounds {
             self.min.x <= pos.x && self.min.y <= pos.y && self.min.z <= pos.z && self.max.x >= pos.x && self.max.y >= pos.y && self.max.z >= pos.z
         }
 
+        pub fn intersects(&self, other: Bounds) -> bool {
+            self.min.x <= other.max.x && self.max.x >= other.min.x &&
+                self.min.y <= other.max.y && self.max.y >= other.min.y &&
+                self.min.z <= other.max.z && self.max.z >= other.min.z
+        }
+
+        pub fn intersection(&self, other: Bounds) -> Option<Bounds> {
+            if !self.intersects(other) { return None; }
+            Some(bounds(
+                point(cmp::max(self.min.x, other.min.x), cmp::max(self.min.y, other.min.y), cmp::max(self.min.z, other.min.z)),
+                point(cmp::min(self.max.x, other.max.x), cmp::min(self.max.y, other.max.y), cmp::min(self.max.z, other.max.z))))
+        }
+
+        // The parts of self not in other, as up to six disjoint bounds
+        pub fn difference(&self, other: Bounds) -> Vec<Bounds> {
+            let overlap = match self.intersection(other) {
+                Some(overlap) => overlap,
+                None => return vec![*self],
+            };
+            let mut pieces = Vec::with_capacity(6);
+            // Full slabs below and above the overlap in z, then full-width pieces beside it in y,
+            // and finally the pieces beside it in x
+            if self.min.z < overlap.min.z {
+                pieces.push(bounds(self.min, point(self.max.x, self.max.y, overlap.min.z - 1)));
+            }
+            if overlap.max.z < self.max.z {
+                pieces.push(bounds(point(self.min.x, self.min.y, overlap.max.z + 1), self.max));
+            }
+            let (z1, z2) = (overlap.min.z, overlap.max.z);
+            if self.min.y < overlap.min.y {
+                pieces.push(bounds(point(self.min.x, self.min.y, z1), point(self.max.x, overlap.min.y - 1, z2)));
+            }
+            if overlap.max.y < self.max.y {
+                pieces.push(bounds(point(self.min.x, overlap.max.y + 1, z1), point(self.max.x, self.max.y, z2)));
+            }
+            let (y1, y2) = (overlap.min.y, overlap.max.y);
+            if self.min.x < overlap.min.x {
+                pieces.push(bounds(point(self.min.x, y1, z1), point(overlap.min.x - 1, y2, z2)));
+            }
+            if overlap.max.x < self.max.x {
+                pieces.push(bounds(point(overlap.max.x + 1, y1, z1), point(self.max.x, y2, z2)));
+            }
+            pieces
+        }
+
+        // The length of each side, counting both ends
+        pub fn size(&self) -> Vector {
+            vector(self.max.x - self.min.x + 1, self.max.y - self.min.y + 1, self.max.z - self.min.z + 1)
+        }
+
+        // The number of points in the bounds
+        pub fn volume(&self) -> i64 {
+            let size = self.size();
+            size.x as i64 * size.y as i64 * size.z as i64
+        }
+
+        // The projections onto the xy, xz, and yz planes, i.e. the shadows the bounds cast along
+        // each axis
+        pub fn xy(&self) -> euclid::Bounds {
+            euclid::bounds(euclid::point(self.min.x, self.min.y), euclid::point(self.max.x, self.max.y))
+        }
+
+        pub fn xz(&self) -> euclid::Bounds {
+            euclid::bounds(euclid::point(self.min.x, self.min.z), euclid::point(self.max.x, self.max.z))
+        }
+
+        pub fn yz(&self) -> euclid::Bounds {
+            euclid::bounds(euclid::point(self.min.y, self.min.z), euclid::point(self.max.y, self.max.z))
+        }
+
+        // The number of points in any of the given bounds, counting points where they overlap once
+        pub fn union_volume<'a>(all: impl IntoIterator<Item = &'a Bounds>) -> i64 {
+            let mut disjoint: Vec<Bounds> = Vec::new();
+            for b in all {
+                disjoint = disjoint.iter().flat_map(|d| d.difference(*b)).collect();
+                disjoint.push(*b);
+            }
+            disjoint.iter().map(|b| b.volume()).sum()
+        }
+
+        // Grows the bounds by margin in every direction
+        pub fn expand(&self, margin: i32) -> Bounds {
+            assert!(margin >= 0, "Use shrink() instead");
+            bounds(self.min + vector(-margin, -margin, -margin), self.max + vector(margin, margin, margin))
+        }
+
+        // Shrinks the bounds by margin in every direction, or None if nothing would be left
+        pub fn shrink(&self, margin: i32) -> Option<Bounds> {
+            assert!(margin >= 0, "Use expand() instead");
+            let (min, max) = (self.min + vector(margin, margin, margin), self.max + vector(-margin, -margin, -margin));
+            if min.x > max.x || min.y > max.y || min.z > max.z { return None; }
+            Some(bounds(min, max))
+        }
+
         pub fn iter(&self) -> impl Iterator<Item = Point> + '_ {
             self.iter_planes().flatten().flatten()
         }
@@ -207,6 +303,69 @@ mod bounds {
             assert!(!bound.contains(five_six_seven));
         }
 
+        #[test]
+        fn intersection() {
+            let a = bounds(point(0, 0, 0), point(3, 3, 3));
+            assert_eq!(a.intersection(bounds(point(1, -1, 2), point(4, 2, 5))), Some(bounds(point(1, 0, 2), point(3, 2, 3))));
+            assert_eq!(a.intersection(bounds(point(3, 3, 3), point(4, 4, 4))), Some(bounds(point(3, 3, 3), point(3, 3, 3))));
+            assert!(!a.intersects(bounds(point(0, 0, 4), point(3, 3, 5))));
+            assert_eq!(a.intersection(bounds(point(0, 0, 4), point(3, 3, 5))), None);
+        }
+
+        parameterized_test::create!{ differences, (a, b, pieces), {
+            let difference = a.difference(b);
+            assert_eq!(difference.len(), pieces);
+            assert_eq!(difference.iter().map(|d| d.volume()).sum::<i64>(), a.volume() - a.intersection(b).map(|i| i.volume()).unwrap_or(0));
+            for (i, d) in difference.iter().enumerate() {
+                assert!(!d.intersects(b), "{:?}", d);
+                assert!(difference[i+1..].iter().all(|o| !d.intersects(*o)), "{:?}", difference);
+            }
+        }}
+        differences! {
+            disjoint: (bounds(point(0, 0, 0), point(3, 3, 3)), bounds(point(5, 5, 5), point(6, 6, 6)), 1),
+            covered: (bounds(point(1, 1, 1), point(2, 2, 2)), bounds(point(0, 0, 0), point(3, 3, 3)), 0),
+            hole: (bounds(point(0, 0, 0), point(4, 4, 4)), bounds(point(2, 2, 2), point(2, 2, 2)), 6),
+            corner: (bounds(point(0, 0, 0), point(3, 3, 3)), bounds(point(2, 2, 2), point(5, 5, 5)), 3),
+        }
+
+        #[test]
+        fn size() {
+            let bound = bounds(point(0, -1, 2), point(3, 1, 2));
+            assert_eq!(bound.size(), vector(4, 3, 1));
+            assert_eq!(bound.volume(), 12);
+            assert_eq!(bound.volume(), bound.iter().count() as i64);
+            assert_eq!(bounds(point(0, 0, 0), point(99999, 99999, 99999)).volume(), 1_000_000_000_000_000);
+        }
+
+        #[test]
+        fn projections() {
+            let bound = bounds(point(0, -1, 2), point(3, 1, 5));
+            assert_eq!(bound.xy(), euclid::bounds(euclid::point(0, -1), euclid::point(3, 1)));
+            assert_eq!(bound.xz(), euclid::bounds(euclid::point(0, 2), euclid::point(3, 5)));
+            assert_eq!(bound.yz(), euclid::bounds(euclid::point(-1, 2), euclid::point(1, 5)));
+            assert_eq!(bound.xy().area() as i64 * bound.size().z as i64, bound.volume());
+            // Bounds that don't intersect may still overlap in some projections
+            let above = bounds(point(1, 0, 7), point(2, 0, 9));
+            assert!(!bound.intersects(above));
+            assert!(bound.xy().intersects(above.xy()));
+            assert!(!bound.xz().intersects(above.xz()));
+        }
+
+        #[test]
+        fn union_volume() {
+            let all = [bounds(point(0, 0, 0), point(2, 2, 2)), bounds(point(1, 1, 1), point(3, 3, 3)), bounds(point(0, 0, 0), point(0, 0, 0))];
+            assert_eq!(Bounds::union_volume(&all), 27 + 27 - 8);
+            assert_eq!(Bounds::union_volume(&[]), 0);
+        }
+
+        #[test]
+        fn margins() {
+            let bound = bounds(point(0, 0, 0), point(3, 2, 4));
+            assert_eq!(bound.expand(1), bounds(point(-1, -1, -1), point(4, 3, 5)));
+            assert_eq!(bound.shrink(1), Some(bounds(point(1, 1, 1), point(2, 1, 3))));
+            assert_eq!(bound.shrink(2), None);
+        }
+
         #[test]
         fn display_bounds() {
             let points = [point(1, 2, 1), point(3, 1, 2), point(0, -1, 1)];
@@ -264,6 +423,56 @@ mod vector {
         pub fn grid_len(&self) -> u32 {
             (self.x.abs() + self.y.abs() + self.z.abs()) as u32
         }
+
+        // The number of moves if diagonal moves are allowed, i.e. the Chebyshev distance
+        pub fn king_len(&self) -> u32 {
+            cmp::max(cmp::max(self.x.abs(), self.y.abs()), self.z.abs()) as u32
+        }
+
+        // The square of len(), which unlike len() is exact
+        pub fn len_squared(&self) -> i64 {
+            self.dot(*self)
+        }
+
+        pub fn dot(&self, other: Vector) -> i64 {
+            self.x as i64 * other.x as i64 + self.y as i64 * other.y as i64 + self.z as i64 * other.z as i64
+        }
+
+        // Perpendicular to both vectors, following the right-hand rule
+        pub fn cross(&self, other: Vector) -> Vector {
+            vector(self.y * other.z - self.z * other.y, self.z * other.x - self.x * other.z, self.x * other.y - self.y * other.x)
+        }
+    }
+
+    impl Add for Vector {
+        type Output = Vector;
+
+        fn add(self, vec: Vector) -> Vector { vector(self.x + vec.x, self.y + vec.y, self.z + vec.z) }
+    }
+
+    impl Sub for Vector {
+        type Output = Vector;
+
+        fn sub(self, vec: Vector) -> Vector { vector(self.x - vec.x, self.y - vec.y, self.z - vec.z) }
+    }
+
+    impl Neg for Vector {
+        type Output = Vector;
+
+        fn neg(self) -> Vector { vector(-self.x, -self.y, -self.z) }
+    }
+
+    impl Mul<i32> for Vector {
+        type Output = Vector;
+
+        fn mul(self, m: i32) -> Vector { vector(self.x * m, self.y * m, self.z * m) }
+    }
+
+    // Integer division, so each component is truncated towards zero
+    impl Div<i32> for Vector {
+        type Output = Vector;
+
+        fn div(self, d: i32) -> Vector { vector(self.x / d, self.y / d, self.z / d) }
     }
 
     impl FromStr for Vector {
@@ -323,6 +532,891 @@ mod vector {
             d: (point(1,1,1), point(8,3,5), 13),
             e: (point(1,1,1), point(-1,-1,-1), 6),
         }
+
+        parameterized_test::create!{ metrics, (v, king, squared), {
+            assert_eq!(v.king_len(), king);
+            assert_eq!((-v).king_len(), king);
+            assert_eq!(v.len_squared(), squared);
+            assert_approx_eq!((v.len_squared() as f64).sqrt(), v.len(), 1e-9);
+        }}
+        metrics! {
+            zero: (vector(0, 0, 0), 0, 0),
+            straight: (vector(0, 0, -4), 4, 16),
+            diagonal: (vector(3, -3, 3), 3, 27),
+            mixed: (vector(7, -2, 4), 7, 69),
+        }
+
+        #[test]
+        fn products() {
+            let (x, y, z) = (vector(1, 0, 0), vector(0, 1, 0), vector(0, 0, 1));
+            assert_eq!(x.cross(y), z);
+            assert_eq!(y.cross(x), -z);
+            assert_eq!(y.cross(z), x);
+            let (a, b) = (vector(2, 1, -3), vector(-1, 4, 2));
+            assert_eq!(a.dot(b), -4);
+            assert_eq!(a.cross(b).dot(a), 0);
+            assert_eq!(a.cross(b).dot(b), 0);
+            assert_eq!(a.cross(a * 2), vector(0, 0, 0));
+            assert_eq!(vector(1_000_000, 0, 0).len_squared(), 1_000_000_000_000);
+        }
+
+        #[test]
+        fn operators() {
+            assert_eq!(vector(2, 1, 0) + vector(-1, 3, 1) - vector(1, 1, 1), vector(0, 3, 0));
+            assert_eq!(vector(6, -4, 7) / 2, vector(3, -2, 3));
+        }
     }
 }
 pub use self::vector::{Vector,vector};
+
+mod rotation {
+    use super::*;
+    use std::collections::HashMap;
+
+    // One of the 24 ways to rotate space about the origin so that each axis lines up with an axis,
+    // i.e. the orientations of a die. Stored as a rotation matrix, which is a permutation matrix
+    // with some entries negated.
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Rotation {
+        matrix: [[i32; 3]; 3],
+    }
+
+    impl Rotation {
+        pub const IDENTITY: Rotation = Rotation{ matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]] };
+        // Quarter-turns counterclockwise about each axis, as seen looking from the positive end of
+        // the axis towards the origin
+        pub const ABOUT_X: Rotation = Rotation{ matrix: [[1, 0, 0], [0, 0, -1], [0, 1, 0]] };
+        pub const ABOUT_Y: Rotation = Rotation{ matrix: [[0, 0, 1], [0, 1, 0], [-1, 0, 0]] };
+        pub const ABOUT_Z: Rotation = Rotation{ matrix: [[0, -1, 0], [1, 0, 0], [0, 0, 1]] };
+
+        // Every rotation, starting with the identity
+        pub fn all() -> [Rotation; 24] {
+            let mut all = Vec::with_capacity(24);
+            for axes in [[0, 1, 2], [1, 2, 0], [2, 0, 1], [0, 2, 1], [2, 1, 0], [1, 0, 2]] {
+                for signs in 0..8 {
+                    let mut matrix = [[0; 3]; 3];
+                    for (row, &axis) in axes.iter().enumerate() {
+                        matrix[row][axis] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                    }
+                    let rotation = Rotation{ matrix };
+                    // The others are reflections
+                    if rotation.determinant() == 1 { all.push(rotation); }
+                }
+            }
+            all.try_into().expect("24 rotations")
+        }
+
+        fn determinant(&self) -> i32 {
+            let m = &self.matrix;
+            m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+                - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+                + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+        }
+
+        // The rotation that applies self and then other
+        pub fn then(&self, other: Rotation) -> Rotation {
+            let mut matrix = [[0; 3]; 3];
+            for (i, row) in matrix.iter_mut().enumerate() {
+                for (j, cell) in row.iter_mut().enumerate() {
+                    *cell = (0..3).map(|k| other.matrix[i][k] * self.matrix[k][j]).sum();
+                }
+            }
+            Rotation{ matrix }
+        }
+
+        pub fn inverse(&self) -> Rotation {
+            let m = &self.matrix;
+            Rotation{ matrix: [[m[0][0], m[1][0], m[2][0]], [m[0][1], m[1][1], m[2][1]], [m[0][2], m[1][2], m[2][2]]] }
+        }
+
+        pub fn vector(&self, v: Vector) -> Vector {
+            let row = |r: [i32; 3]| r[0] * v.x + r[1] * v.y + r[2] * v.z;
+            vector(row(self.matrix[0]), row(self.matrix[1]), row(self.matrix[2]))
+        }
+
+        pub fn point(&self, p: Point) -> Point {
+            Point::ORIGIN + self.vector(p - Point::ORIGIN)
+        }
+
+        pub fn bounds(&self, b: Bounds) -> Bounds {
+            Bounds::from_points(&[self.point(b.min), self.point(b.max)]).expect("Non-empty")
+        }
+
+        pub fn points<'a, B: FromIterator<Point>>(&self, points: impl IntoIterator<Item = &'a Point>) -> B {
+            points.into_iter().map(|p| self.point(*p)).collect()
+        }
+
+        // Finds a rotation and then translation that moves at least min_overlap of the points in
+        // moving onto points in fixed, if there is one. If several do, the one that overlaps the
+        // most points is returned.
+        pub fn align(fixed: &[Point], moving: &[Point], min_overlap: usize) -> Option<(Rotation, Vector)> {
+            let mut best: Option<(usize, Rotation, Vector)> = None;
+            for rotation in Rotation::all() {
+                let rotated: Vec<Point> = rotation.points(moving);
+                let mut offsets: HashMap<Vector, usize> = HashMap::new();
+                for f in fixed {
+                    for r in &rotated {
+                        *offsets.entry(f - r).or_default() += 1;
+                    }
+                }
+                // Ties are broken by the smallest offset so the result is deterministic
+                if let Some((offset, count)) = offsets.into_iter().max_by_key(|&(o, c)| (c, cmp::Reverse(o))) {
+                    if count >= min_overlap && best.is_none_or(|(b, _, _)| count > b) {
+                        best = Some((count, rotation, offset));
+                    }
+                }
+            }
+            best.map(|(_, rotation, offset)| (rotation, offset))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::collections::HashSet;
+
+        #[test]
+        fn all() {
+            let all = Rotation::all();
+            assert_eq!(all[0], Rotation::IDENTITY);
+            assert_eq!(all.iter().collect::<HashSet<_>>().len(), 24);
+            // Every orientation of the die is distinct
+            let corner = point(1, 2, 3);
+            assert_eq!(all.iter().map(|r| r.point(corner)).collect::<HashSet<_>>().len(), 24);
+            for a in all {
+                assert_eq!(a.then(a.inverse()), Rotation::IDENTITY);
+                assert_eq!(a.inverse().then(a), Rotation::IDENTITY);
+                for b in all {
+                    assert!(all.contains(&a.then(b)));
+                }
+            }
+        }
+
+        #[test]
+        fn quarter_turns() {
+            assert_eq!(Rotation::ABOUT_Z.vector(vector(1, 0, 0)), vector(0, 1, 0));
+            assert_eq!(Rotation::ABOUT_X.vector(vector(0, 1, 0)), vector(0, 0, 1));
+            assert_eq!(Rotation::ABOUT_Y.vector(vector(0, 0, 1)), vector(1, 0, 0));
+            for turn in [Rotation::ABOUT_X, Rotation::ABOUT_Y, Rotation::ABOUT_Z] {
+                assert_eq!(turn.then(turn).then(turn).then(turn), Rotation::IDENTITY);
+                assert_eq!(turn.then(turn).then(turn), turn.inverse());
+            }
+            let v = vector(2, -1, 3);
+            assert_eq!(Rotation::ABOUT_X.then(Rotation::ABOUT_Z).vector(v), Rotation::ABOUT_Z.vector(Rotation::ABOUT_X.vector(v)));
+            assert_eq!(Rotation::ABOUT_Z.vector(v).len_squared(), v.len_squared());
+        }
+
+        #[test]
+        fn apply() {
+            let b = bounds(point(0, 0, 0), point(3, 2, 1));
+            let rotated = Rotation::ABOUT_Z.bounds(b);
+            assert_eq!(rotated, bounds(point(-2, 0, 0), point(0, 3, 1)));
+            assert_eq!(rotated.volume(), b.volume());
+            let points: HashSet<Point> = Rotation::ABOUT_Z.points(&b.iter().collect::<Vec<_>>());
+            assert_eq!(points, rotated.iter().collect());
+        }
+
+        #[test]
+        fn align() {
+            let rng = fastrand::Rng::with_seed(19);
+            let fixed: Vec<_> = (0..25).map(|_| point(rng.i32(-500..500), rng.i32(-500..500), rng.i32(-500..500))).collect();
+            for (i, rotation) in Rotation::all().into_iter().enumerate() {
+                let offset = vector(rng.i32(-1000..1000), rng.i32(-1000..1000), rng.i32(-1000..1000));
+                // Only some of the moving points are also in fixed
+                let mut moving: Vec<_> = fixed[i % 10..].iter().map(|p| rotation.inverse().point(*p + -offset)).collect();
+                moving.push(point(2000, 2000, 2000));
+                assert_eq!(Rotation::align(&fixed, &moving, 12), Some((rotation, offset)));
+                assert_eq!(Rotation::align(&fixed, &moving, 100), None);
+            }
+        }
+    }
+}
+pub use self::rotation::Rotation;
+
+mod linear {
+    // Solves the square system of linear equations matrix * x = rhs by Gaussian elimination, or
+    // returns None if there isn't a unique solution. Exact if T is, e.g. with num::BigRational.
+    pub fn solve_linear<T: num::Num + Clone>(matrix: &[Vec<T>], rhs: &[T]) -> Option<Vec<T>> {
+        let n = rhs.len();
+        assert_eq!(matrix.len(), n, "Need one equation per unknown");
+        let mut rows: Vec<Vec<T>> = matrix.iter().zip(rhs)
+            .map(|(row, r)| {
+                assert_eq!(row.len(), n, "Need one coefficient per unknown");
+                row.iter().cloned().chain([r.clone()]).collect()
+            })
+            .collect();
+
+        for col in 0..n {
+            let pivot = (col..n).find(|&r| !rows[r][col].is_zero())?;
+            rows.swap(col, pivot);
+            let pivot = rows[col].clone();
+            for row in rows.iter_mut().skip(col + 1) {
+                if row[col].is_zero() { continue; }
+                let factor = row[col].clone() / pivot[col].clone();
+                for (cell, p) in row.iter_mut().zip(&pivot).skip(col) {
+                    *cell = cell.clone() - factor.clone() * p.clone();
+                }
+            }
+        }
+
+        // Back-substitution, from the last unknown to the first
+        let mut solution = vec![T::zero(); n];
+        for i in (0..n).rev() {
+            let known = (i+1..n).fold(T::zero(), |sum, j| sum + rows[i][j].clone() * solution[j].clone());
+            solution[i] = (rows[i][n].clone() - known) / rows[i][i].clone();
+        }
+        Some(solution)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use num::rational::Ratio;
+
+        #[test]
+        fn solves() {
+            let r = |n: i64| Ratio::from_integer(n);
+            // 2x + y - z = 8, -3x - y + 2z = -11, -2x + y + 2z = -3
+            let matrix = vec![vec![r(2), r(1), r(-1)], vec![r(-3), r(-1), r(2)], vec![r(-2), r(1), r(2)]];
+            assert_eq!(solve_linear(&matrix, &[r(8), r(-11), r(-3)]), Some(vec![r(2), r(3), r(-1)]));
+
+            // Requires swapping rows, and has a fractional solution
+            let matrix = vec![vec![r(0), r(2)], vec![r(3), r(1)]];
+            assert_eq!(solve_linear(&matrix, &[r(1), r(1)]), Some(vec![Ratio::new(1, 6), Ratio::new(1, 2)]));
+        }
+
+        #[test]
+        fn singular() {
+            let matrix = vec![vec![1, 2], vec![2, 4]];
+            assert_eq!(solve_linear(&matrix, &[3, 6]), None);
+            assert_eq!(solve_linear::<i32>(&[], &[]), Some(vec![]));
+        }
+    }
+}
+pub use self::linear::solve_linear;
+
+mod ray {
+    use super::*;
+    use anyhow::{bail, ensure};
+    use num::{BigInt, BigRational, ToPrimitive, Zero};
+    use num::rational::Ratio;
+
+    // Exact fractions, e.g. the times at which two rays cross
+    pub type Rational = Ratio<i128>;
+
+    // A point moving in a straight line, e.g. a hailstone. Positions can be too large for Point,
+    // and computations are done with i128s or rationals so that they're exact.
+    #[derive(Copy, Clone, PartialEq, Eq, Hash)]
+    pub struct Ray {
+        pub pos: [i64; 3],
+        pub vel: [i64; 3],
+    }
+
+    // A Ray projected onto a plane, i.e. ignoring one of its axes
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Ray2d {
+        pub pos: euclid::Point<i128>,
+        pub vel: euclid::Vector<i128>,
+    }
+
+    impl Ray {
+        pub const fn new(pos: [i64; 3], vel: [i64; 3]) -> Ray {
+            Ray{ pos, vel }
+        }
+
+        pub fn at(&self, t: i64) -> [i128; 3] {
+            std::array::from_fn(|i| self.pos[i] as i128 + self.vel[i] as i128 * t as i128)
+        }
+
+        fn project(&self, a: usize, b: usize) -> Ray2d {
+            Ray2d{
+                pos: euclid::point(self.pos[a] as i128, self.pos[b] as i128),
+                vel: euclid::vector(self.vel[a] as i128, self.vel[b] as i128),
+            }
+        }
+
+        pub fn xy(&self) -> Ray2d { self.project(0, 1) }
+
+        pub fn xz(&self) -> Ray2d { self.project(0, 2) }
+
+        pub fn yz(&self) -> Ray2d { self.project(1, 2) }
+
+        // The time at which both rays are in the same place, if they ever are. Identical rays are
+        // always in the same place, so this returns zero.
+        pub fn collision_time(&self, other: &Ray) -> Option<Rational> {
+            let mut time = None;
+            for i in 0..3 {
+                let gap = other.pos[i] as i128 - self.pos[i] as i128;
+                let closing = self.vel[i] as i128 - other.vel[i] as i128;
+                if closing == 0 {
+                    if gap != 0 { return None; }
+                    continue;
+                }
+                let t = Rational::new(gap, closing);
+                if time.is_some_and(|time| time != t) { return None; }
+                time = Some(t);
+            }
+            Some(time.unwrap_or_else(Rational::zero))
+        }
+
+        // The ray that collides with every one of rays, assuming there is one with an integer
+        // position and velocity. A ray (P, V) that hits rays i and j satisfies both
+        // (P - p_i) x (V - v_i) = 0 and (P - p_j) x (V - v_j) = 0, and subtracting them cancels
+        // the non-linear P x V term. Two such pairs give six linear equations in six unknowns.
+        pub fn intercepting(rays: &[Ray]) -> Result<Ray> {
+            ensure!(rays.len() >= 3, "Need at least three rays");
+            let solution = rays.windows(3)
+                .find_map(|w| solve_linear(&[equations(&w[0], &w[1]), equations(&w[0], &w[2])].concat(),
+                                           &[constants(&w[0], &w[1]), constants(&w[0], &w[2])].concat()))
+                .context("No three rays determine an intercepting ray")?;
+            let integers = solution.iter()
+                .map(|v| if v.is_integer() { v.to_integer().to_i64() } else { None })
+                .collect::<Option<Vec<_>>>()
+                .with_context(|| format!("Intercepting ray is not integral: {:?}", solution))?;
+            let ret = Ray::new([integers[0], integers[1], integers[2]], [integers[3], integers[4], integers[5]]);
+            for ray in rays {
+                if ret.collision_time(ray).is_none() { bail!("{} does not collide with {}", ret, ray); }
+            }
+            Ok(ret)
+        }
+    }
+
+    fn big(n: i64) -> BigRational { BigRational::from_integer(BigInt::from(n)) }
+
+    // The coefficients of (P, V) in P x (v_j - v_i) + (p_j - p_i) x V = p_j x v_j - p_i x v_i
+    fn equations(a: &Ray, b: &Ray) -> Vec<Vec<BigRational>> {
+        let w: [i64; 3] = std::array::from_fn(|i| b.vel[i] - a.vel[i]);
+        let d: [i64; 3] = std::array::from_fn(|i| b.pos[i] - a.pos[i]);
+        [
+            [0, w[2], -w[1], 0, -d[2], d[1]],
+            [-w[2], 0, w[0], d[2], 0, -d[0]],
+            [w[1], -w[0], 0, -d[1], d[0], 0],
+        ].iter().map(|row| row.iter().map(|&c| big(c)).collect()).collect()
+    }
+
+    fn constants(a: &Ray, b: &Ray) -> Vec<BigRational> {
+        let cross = |r: &Ray| {
+            let (p, v) = (r.pos.map(BigInt::from), r.vel.map(BigInt::from));
+            [&p[1] * &v[2] - &p[2] * &v[1], &p[2] * &v[0] - &p[0] * &v[2], &p[0] * &v[1] - &p[1] * &v[0]]
+        };
+        let (ca, cb) = (cross(a), cross(b));
+        (0..3).map(|i| BigRational::from_integer(&cb[i] - &ca[i])).collect()
+    }
+
+    impl Ray2d {
+        pub fn at(&self, t: Rational) -> (Rational, Rational) {
+            (t * self.vel.x + self.pos.x, t * self.vel.y + self.pos.y)
+        }
+
+        // The times at which self and other respectively reach the point where their paths cross,
+        // or None if they're parallel. Either time may be negative, i.e. in the past.
+        pub fn crossing(&self, other: &Ray2d) -> Option<(Rational, Rational)> {
+            let denom = self.vel.cross(other.vel);
+            if denom == 0 { return None; }
+            let offset = other.pos - self.pos;
+            Some((Rational::new(offset.cross(other.vel), denom), Rational::new(offset.cross(self.vel), denom)))
+        }
+    }
+
+    // Parses e.g. 19, 13, 30 @ -2, 1, -2
+    impl FromStr for Ray {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            let (pos, vel) = s.split_once('@').with_context(|| format!("Invalid ray '{}'", s))?;
+            let parse = |t: &str| -> Result<[i64; 3]> {
+                let values = t.split(',').map(|v| v.trim().parse()).collect::<Result<Vec<i64>, _>>()?;
+                values.try_into().map_err(|_| anyhow::anyhow!("Invalid ray '{}'", s))
+            };
+            Ok(Ray::new(parse(pos)?, parse(vel)?))
+        }
+    }
+
+    impl fmt::Debug for Ray {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let (p, v) = (self.pos, self.vel);
+            write!(f, "{}, {}, {} @ {}, {}, {}", p[0], p[1], p[2], v[0], v[1], v[2])
+        }
+    }
+
+    impl fmt::Display for Ray {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // From 2023 Day 24
+        fn example() -> Vec<Ray> {
+            ["19, 13, 30 @ -2,  1, -2", "18, 19, 22 @ -1, -1, -2", "20, 25, 34 @ -2, -2, -4",
+             "12, 31, 28 @ -1, -2, -1", "20, 19, 15 @  1, -5, -3"].iter().map(|r| r.parse().unwrap()).collect()
+        }
+
+        #[test]
+        fn parse() {
+            let ray: Ray = "19, 13, 30 @ -2,  1, -2".parse().unwrap();
+            assert_eq!(ray, Ray::new([19, 13, 30], [-2, 1, -2]));
+            assert_eq!(ray.to_string(), "19, 13, 30 @ -2, 1, -2");
+            assert_eq!(ray.at(5), [9, 18, 20]);
+            assert!("19, 13 @ -2, 1".parse::<Ray>().is_err());
+            assert!("19, 13, 30".parse::<Ray>().is_err());
+        }
+
+        parameterized_test::create!{ crossings, (a, b, expected), {
+            let rays = example();
+            let crossing = rays[a].xy().crossing(&rays[b].xy());
+            assert_eq!(crossing.map(|(t, _)| rays[a].xy().at(t)), expected.map(|(x, y)| (Rational::new(x, 3), Rational::new(y, 3))));
+            if let Some((t, u)) = crossing {
+                assert_eq!(rays[a].xy().at(t), rays[b].xy().at(u));
+            }
+        }}
+        crossings! {
+            inside: (0, 1, Some((43, 46))),
+            also_inside: (0, 2, Some((35, 50))),
+            outside: (1, 3, Some((-18, -15))),
+            parallel: (1, 2, None),
+        }
+
+        #[test]
+        fn in_test_area() {
+            let rays = example();
+            let area = (Rational::from_integer(7), Rational::from_integer(27));
+            let inside = |(x, y): (Rational, Rational)| area.0 <= x && x <= area.1 && area.0 <= y && y <= area.1;
+            let count = rays.iter().enumerate()
+                .flat_map(|(i, a)| rays[i+1..].iter().map(move |b| (a.xy(), b.xy())))
+                .filter_map(|(a, b)| a.crossing(&b).map(|(t, u)| (a, t, u)))
+                .filter(|&(a, t, u)| t >= Rational::zero() && u >= Rational::zero() && inside(a.at(t)))
+                .count();
+            assert_eq!(count, 2);
+        }
+
+        #[test]
+        fn collisions() {
+            let rock = Ray::new([24, 13, 10], [-3, 1, 2]);
+            let times: Vec<_> = example().iter().map(|r| rock.collision_time(r)).collect();
+            assert_eq!(times, [5, 3, 4, 6, 1].map(|t| Some(Rational::from_integer(t))));
+            assert_eq!(rock.collision_time(&rock), Some(Rational::zero()));
+            assert_eq!(rock.collision_time(&Ray::new([24, 13, 11], [-3, 1, 2])), None);
+        }
+
+        #[test]
+        fn intercepting() {
+            assert_eq!(Ray::intercepting(&example()).unwrap(), Ray::new([24, 13, 10], [-3, 1, 2]));
+            assert!(Ray::intercepting(&example()[..2]).is_err());
+        }
+
+        #[test]
+        fn intercepting_large() {
+            // Similar in scale to real inputs, which are far too large for f64 to be exact
+            let rng = fastrand::Rng::with_seed(24);
+            let rock = Ray::new([rng.i64(1e14 as i64..4e14 as i64), rng.i64(1e14 as i64..4e14 as i64), rng.i64(1e14 as i64..4e14 as i64)],
+                                [rng.i64(-300..300), rng.i64(-300..300), rng.i64(-300..300)]);
+            let hail: Vec<_> = (0..5).map(|_| {
+                let t = rng.i64(1e11 as i64..1e12 as i64);
+                let vel = [rng.i64(-500..500), rng.i64(-500..500), rng.i64(-500..500)];
+                let hit = rock.at(t);
+                Ray::new(std::array::from_fn(|i| (hit[i] - vel[i] as i128 * t as i128) as i64), vel)
+            }).collect();
+            assert_eq!(Ray::intercepting(&hail).unwrap(), rock);
+        }
+    }
+}
+pub use self::ray::{Rational, Ray, Ray2d};
+
+mod export {
+    use super::*;
+    use std::fmt::Write;
+    use anyhow::bail;
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct Color {
+        pub r: u8,
+        pub g: u8,
+        pub b: u8,
+    }
+
+    impl Color {
+        pub const fn rgb(r: u8, g: u8, b: u8) -> Color { Color{ r, g, b } }
+
+        // A color for the i-th object, so that neighboring objects are easy to tell apart
+        pub fn indexed(i: usize) -> Color {
+            const PALETTE: [Color; 8] = [
+                Color::rgb(0xe6, 0x19, 0x4b), Color::rgb(0x3c, 0xb4, 0x4b), Color::rgb(0xff, 0xe1, 0x19),
+                Color::rgb(0x43, 0x63, 0xd8), Color::rgb(0xf5, 0x82, 0x31), Color::rgb(0x91, 0x1e, 0xb4),
+                Color::rgb(0x46, 0xf0, 0xf0), Color::rgb(0xf0, 0x32, 0xe6)];
+            PALETTE[i % PALETTE.len()]
+        }
+
+        fn fractions(&self) -> [f64; 3] {
+            [self.r, self.g, self.b].map(|c| c as f64 / 255.0)
+        }
+    }
+
+    impl fmt::Display for Color {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub enum Format {
+        OpenScad,
+        // Wavefront OBJ, with colors as the widely-supported but unofficial vertex colors
+        Obj,
+        // ASCII STL, which has no colors
+        Stl,
+    }
+
+    impl FromStr for Format {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            Ok(match s.to_ascii_lowercase().as_str() {
+                "scad" | "openscad" => Format::OpenScad,
+                "obj" => Format::Obj,
+                "stl" => Format::Stl,
+                _ => bail!("Unknown format: {}", s),
+            })
+        }
+    }
+
+    // The corners of each face of a unit cube, counterclockwise as seen from outside, and the
+    // face's outward normal
+    const FACES: [([[i32; 3]; 4], [i32; 3]); 6] = [
+        ([[0, 0, 0], [0, 0, 1], [0, 1, 1], [0, 1, 0]], [-1, 0, 0]),
+        ([[1, 0, 0], [1, 1, 0], [1, 1, 1], [1, 0, 1]], [1, 0, 0]),
+        ([[0, 0, 0], [1, 0, 0], [1, 0, 1], [0, 0, 1]], [0, -1, 0]),
+        ([[0, 1, 0], [0, 1, 1], [1, 1, 1], [1, 1, 0]], [0, 1, 0]),
+        ([[0, 0, 0], [0, 1, 0], [1, 1, 0], [1, 0, 0]], [0, 0, -1]),
+        ([[0, 0, 1], [1, 0, 1], [1, 1, 1], [0, 1, 1]], [0, 0, 1]),
+    ];
+
+    // A collection of boxes to render, e.g. to inspect a puzzle's state in a 3D viewer. Each
+    // Bounds is drawn as the unit cubes of the points it contains, so point(0, 0, 0) is the cube
+    // from (0, 0, 0) to (1, 1, 1).
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct Scene {
+        objects: Vec<(Bounds, Option<Color>)>,
+    }
+
+    impl Scene {
+        pub fn new() -> Scene { Scene::default() }
+
+        pub fn add(&mut self, bounds: Bounds, color: Option<Color>) {
+            self.objects.push((bounds, color));
+        }
+
+        pub fn add_voxels<'a>(&mut self, points: impl IntoIterator<Item = &'a Point>, color: Option<Color>) {
+            for p in points {
+                self.add(bounds(*p, *p), color);
+            }
+        }
+
+        pub fn export(&self, format: Format) -> String {
+            match format {
+                Format::OpenScad => self.openscad(),
+                Format::Obj => self.obj(),
+                Format::Stl => self.stl(),
+            }
+        }
+
+        // The corner of the bounds' cubes selected by c, e.g. [0, 0, 0] is the minimum corner
+        fn corner(bounds: &Bounds, c: [i32; 3]) -> Point {
+            point(bounds.min.x + c[0] * bounds.size().x, bounds.min.y + c[1] * bounds.size().y, bounds.min.z + c[2] * bounds.size().z)
+        }
+
+        fn corner_index(c: [i32; 3]) -> usize {
+            (c[0] + 2 * c[1] + 4 * c[2]) as usize
+        }
+
+        pub fn openscad(&self) -> String {
+            let mut out = String::new();
+            for (bounds, color) in &self.objects {
+                if let Some(color) = color { write!(out, "color(\"{}\") ", color).expect("Infallible"); }
+                let (min, size) = (bounds.min, bounds.size());
+                writeln!(out, "translate([{}, {}, {}]) cube([{}, {}, {}]);", min.x, min.y, min.z, size.x, size.y, size.z)
+                    .expect("Infallible");
+            }
+            out
+        }
+
+        pub fn obj(&self) -> String {
+            let mut out = String::new();
+            for (i, (bounds, color)) in self.objects.iter().enumerate() {
+                writeln!(out, "o box{}", i + 1).expect("Infallible");
+                for index in 0..8 {
+                    let p = Scene::corner(bounds, [index & 1, (index >> 1) & 1, index >> 2]);
+                    write!(out, "v {} {} {}", p.x, p.y, p.z).expect("Infallible");
+                    if let Some(color) = color {
+                        let [r, g, b] = color.fractions();
+                        write!(out, " {:.3} {:.3} {:.3}", r, g, b).expect("Infallible");
+                    }
+                    out.push('\n');
+                }
+                for (corners, _) in FACES {
+                    // OBJ indexes vertices from 1, across the whole file
+                    let indexes = corners.map(|c| (8 * i + Scene::corner_index(c) + 1).to_string());
+                    writeln!(out, "f {}", indexes.join(" ")).expect("Infallible");
+                }
+            }
+            out
+        }
+
+        pub fn stl(&self) -> String {
+            let mut out = "solid scene\n".to_string();
+            for (bounds, _) in &self.objects {
+                for (corners, normal) in FACES {
+                    let p = corners.map(|c| Scene::corner(bounds, c));
+                    for triangle in [[p[0], p[1], p[2]], [p[0], p[2], p[3]]] {
+                        writeln!(out, "  facet normal {} {} {}\n    outer loop", normal[0], normal[1], normal[2]).expect("Infallible");
+                        for v in triangle {
+                            writeln!(out, "      vertex {} {} {}", v.x, v.y, v.z).expect("Infallible");
+                        }
+                        writeln!(out, "    endloop\n  endfacet").expect("Infallible");
+                    }
+                }
+            }
+            out.push_str("endsolid scene\n");
+            out
+        }
+    }
+
+    impl FromIterator<Bounds> for Scene {
+        fn from_iter<I: IntoIterator<Item = Bounds>>(iter: I) -> Self {
+            Scene{ objects: iter.into_iter().map(|b| (b, None)).collect() }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn example() -> Scene {
+            let mut scene = Scene::new();
+            scene.add(bounds(point(0, 0, 0), point(1, 0, 2)), Some(Color::rgb(255, 0, 0)));
+            scene.add_voxels(&[point(3, 3, 3)], None);
+            scene
+        }
+
+        #[test]
+        fn faces() {
+            for (corners, normal) in FACES {
+                let c = corners.map(|c| point(c[0], c[1], c[2]));
+                // Counterclockwise as seen from outside, i.e. following the right-hand rule
+                assert_eq!((c[1] - c[0]).cross(c[2] - c[1]), vector(normal[0], normal[1], normal[2]));
+                assert_eq!((c[3] - c[2]).cross(c[0] - c[3]), vector(normal[0], normal[1], normal[2]));
+            }
+        }
+
+        #[test]
+        fn openscad() {
+            assert_eq!(example().export(Format::OpenScad),
+                       "color(\"#ff0000\") translate([0, 0, 0]) cube([2, 1, 3]);\ntranslate([3, 3, 3]) cube([1, 1, 1]);\n");
+        }
+
+        #[test]
+        fn obj() {
+            let obj = example().export(Format::Obj);
+            let lines: Vec<_> = obj.lines().collect();
+            assert_eq!(lines.len(), 2 * (1 + 8 + 6));
+            assert_eq!(lines[0], "o box1");
+            assert_eq!(lines[1], "v 0 0 0 1.000 0.000 0.000");
+            assert_eq!(lines[8], "v 2 1 3 1.000 0.000 0.000");
+            assert_eq!(lines[9], "f 1 5 7 3");
+            assert_eq!(lines[16], "v 3 3 3");
+            assert_eq!(lines[29], "f 13 14 16 15");
+        }
+
+        #[test]
+        fn stl() {
+            let stl = example().export(Format::Stl);
+            assert!(stl.starts_with("solid scene\n  facet normal -1 0 0\n    outer loop\n      vertex 0 0 0\n      vertex 0 0 3\n"), "{}", stl);
+            assert!(stl.ends_with("endsolid scene\n"));
+            assert_eq!(stl.matches("facet normal").count(), 2 * 12);
+            assert_eq!(stl.matches("vertex").count(), 2 * 12 * 3);
+        }
+
+        #[test]
+        fn formats() {
+            assert_eq!("scad".parse::<Format>().unwrap(), Format::OpenScad);
+            assert_eq!("STL".parse::<Format>().unwrap(), Format::Stl);
+            assert!("png".parse::<Format>().is_err());
+            let scene: Scene = [bounds(point(0, 0, 0), point(0, 0, 0))].into_iter().collect();
+            assert_eq!(scene.openscad(), "translate([0, 0, 0]) cube([1, 1, 1]);\n");
+        }
+    }
+}
+pub use self::export::{Color, Format, Scene};
+
+mod voxels {
+    use super::*;
+    use std::collections::{BTreeMap, HashSet, VecDeque};
+    use itertools::Itertools;
+
+    // A set of unit cubes, e.g. a lava droplet. Two voxels are connected if they share a face.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct VoxelSet {
+        voxels: HashSet<Point>,
+    }
+
+    impl VoxelSet {
+        pub fn new() -> VoxelSet { VoxelSet::default() }
+
+        pub fn insert(&mut self, pos: Point) -> bool {
+            self.voxels.insert(pos)
+        }
+
+        pub fn contains(&self, pos: Point) -> bool {
+            self.voxels.contains(&pos)
+        }
+
+        pub fn len(&self) -> usize { self.voxels.len() }
+
+        pub fn is_empty(&self) -> bool { self.voxels.is_empty() }
+
+        pub fn iter(&self) -> impl Iterator<Item = &Point> + '_ {
+            self.voxels.iter()
+        }
+
+        pub fn bounds(&self) -> Option<Bounds> {
+            Bounds::from_points(&self.voxels)
+        }
+
+        fn neighbors(pos: Point) -> impl Iterator<Item = Point> {
+            Vector::CARDINAL.iter().map(move |v| pos + v)
+        }
+
+        // The number of faces that aren't shared with another voxel, including those facing
+        // enclosed pockets of air
+        pub fn surface_area(&self) -> usize {
+            self.voxels.iter()
+                .map(|&v| VoxelSet::neighbors(v).filter(|n| !self.contains(*n)).count())
+                .sum()
+        }
+
+        // The number of faces that can be reached from outside the set
+        pub fn exterior_surface_area(&self) -> usize {
+            let bounds = match self.bounds() {
+                Some(bounds) => bounds.expand(1),
+                None => return 0,
+            };
+            // Flood fill the air around the voxels, counting each face the air touches
+            let mut area = 0;
+            let mut seen = HashSet::from([bounds.min]);
+            let mut frontier = VecDeque::from([bounds.min]);
+            while let Some(air) = frontier.pop_front() {
+                for n in VoxelSet::neighbors(air).filter(|n| bounds.contains(*n)) {
+                    if self.contains(n) {
+                        area += 1;
+                    } else if seen.insert(n) {
+                        frontier.push_back(n);
+                    }
+                }
+            }
+            area
+        }
+
+        // The groups of voxels connected to each other, ordered by their smallest voxel
+        pub fn components(&self) -> Vec<VoxelSet> {
+            let mut unseen: HashSet<Point> = self.voxels.clone();
+            let mut components = Vec::new();
+            for &start in self.voxels.iter().sorted() {
+                if !unseen.remove(&start) { continue; }
+                let mut component = VoxelSet::new();
+                let mut frontier = vec![start];
+                while let Some(pos) = frontier.pop() {
+                    component.insert(pos);
+                    frontier.extend(VoxelSet::neighbors(pos).filter(|n| unseen.remove(n)));
+                }
+                components.push(component);
+            }
+            components
+        }
+
+        // The voxels in the z plane, as 2D points
+        pub fn slice(&self, z: i32) -> HashSet<euclid::Point> {
+            self.voxels.iter().filter(|v| v.z == z).map(|v| euclid::point(v.x, v.y)).collect()
+        }
+
+        // Every non-empty z plane, from lowest to highest
+        pub fn slices(&self) -> BTreeMap<i32, HashSet<euclid::Point>> {
+            let mut slices: BTreeMap<i32, HashSet<euclid::Point>> = BTreeMap::new();
+            for v in &self.voxels {
+                slices.entry(v.z).or_default().insert(euclid::point(v.x, v.y));
+            }
+            slices
+        }
+    }
+
+    impl FromIterator<Point> for VoxelSet {
+        fn from_iter<I: IntoIterator<Item = Point>>(iter: I) -> Self {
+            VoxelSet{ voxels: iter.into_iter().collect() }
+        }
+    }
+
+    // Parses one x,y,z point per line
+    impl FromStr for VoxelSet {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            s.lines().map(|l| l.parse()).collect()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // From 2022 Day 18
+        const DROPLET: &str = "2,2,2\n1,2,2\n3,2,2\n2,1,2\n2,3,2\n2,2,1\n2,2,3\n2,2,4\n2,2,6\n1,2,5\n3,2,5\n2,1,5\n2,3,5";
+
+        parameterized_test::create!{ areas, (voxels, surface, exterior), {
+            let voxels: VoxelSet = voxels.parse().unwrap();
+            assert_eq!(voxels.surface_area(), surface);
+            assert_eq!(voxels.exterior_surface_area(), exterior);
+        }}
+        areas! {
+            empty: ("", 0, 0),
+            single: ("1,1,1", 6, 6),
+            pair: ("1,1,1\n2,1,1", 10, 10),
+            droplet: (DROPLET, 64, 58),
+        }
+
+        #[test]
+        fn hollow() {
+            let cube = bounds(point(0, 0, 0), point(2, 2, 2));
+            let voxels: VoxelSet = cube.iter().filter(|p| *p != point(1, 1, 1)).collect();
+            assert_eq!(voxels.len(), 26);
+            assert_eq!(voxels.surface_area(), 54 + 6);
+            assert_eq!(voxels.exterior_surface_area(), 54);
+            assert_eq!(voxels.bounds(), Some(cube));
+        }
+
+        #[test]
+        fn components() {
+            let voxels: VoxelSet = DROPLET.parse().unwrap();
+            let components = voxels.components();
+            // The plus shape and the voxel above it, and then the voxels around the pocket
+            assert_eq!(components.iter().map(|c| c.len()).collect::<Vec<_>>(), [8, 1, 1, 1, 1, 1]);
+            assert!(components[0].contains(point(2, 2, 4)));
+            assert!(components[1].contains(point(1, 2, 5)));
+            assert_eq!(components.iter().map(|c| c.surface_area()).sum::<usize>(), voxels.surface_area());
+            assert!(VoxelSet::new().components().is_empty());
+        }
+
+        #[test]
+        fn slices() {
+            let voxels: VoxelSet = DROPLET.parse().unwrap();
+            assert_eq!(voxels.slice(2), [euclid::point(2, 2), euclid::point(1, 2), euclid::point(3, 2), euclid::point(2, 1), euclid::point(2, 3)].into_iter().collect());
+            assert!(voxels.slice(0).is_empty());
+            let slices = voxels.slices();
+            assert_eq!(slices.keys().copied().collect::<Vec<_>>(), [1, 2, 3, 4, 5, 6]);
+            assert_eq!(slices.values().map(|s| s.len()).sum::<usize>(), voxels.len());
+            assert_eq!(slices[&5], voxels.slice(5));
+        }
+    }
+}
+pub use self::voxels::VoxelSet;
@@ -0,0 +1,418 @@
+// Points, vectors, and bounds with any number of dimensions, e.g. for cellular automata in 4D.
+// The euclid and euclid3d types are generally more convenient, and convert to and from these.
+
+use std::fmt;
+use std::ops::{Add,AddAssign,Index,IndexMut,Mul,Sub};
+use std::str::FromStr;
+use std::cmp;
+use anyhow::{ensure, Error, Result};
+
+use crate::{euclid, euclid3d};
+
+mod point {
+    use super::*;
+
+    #[derive(Copy, Clone, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    pub struct PointN<const D: usize> {
+        pub coords: [i32; D],
+    }
+
+    #[inline]
+    pub const fn pointn<const D: usize>(coords: [i32; D]) -> PointN<D> {
+        PointN { coords }
+    }
+
+    impl<const D: usize> PointN<D> {
+        pub const ORIGIN: PointN<D> = pointn([0; D]);
+
+        // The 3^D-1 points adjacent to this one, including diagonally
+        pub fn neighbors(&self) -> impl Iterator<Item = PointN<D>> + '_ {
+            VectorN::neighbors().map(move |v| self + v)
+        }
+    }
+
+    impl<const D: usize> Index<usize> for PointN<D> {
+        type Output = i32;
+
+        fn index(&self, axis: usize) -> &i32 { &self.coords[axis] }
+    }
+
+    impl<const D: usize> IndexMut<usize> for PointN<D> {
+        fn index_mut(&mut self, axis: usize) -> &mut i32 { &mut self.coords[axis] }
+    }
+
+    impl<const D: usize> Add<VectorN<D>> for PointN<D> {
+        type Output = PointN<D>;
+
+        fn add(self, vec: VectorN<D>) -> PointN<D> {
+            pointn(std::array::from_fn(|i| self[i] + vec[i]))
+        }
+    }
+
+    impl<const D: usize> Add<VectorN<D>> for &PointN<D> {
+        type Output = PointN<D>;
+
+        fn add(self, vec: VectorN<D>) -> PointN<D> { *self + vec }
+    }
+
+    impl<const D: usize> AddAssign<VectorN<D>> for PointN<D> {
+        fn add_assign(&mut self, vec: VectorN<D>) {
+            *self = *self + vec;
+        }
+    }
+
+    impl<const D: usize> Sub for PointN<D> {
+        type Output = VectorN<D>;
+
+        fn sub(self, point: PointN<D>) -> VectorN<D> { vectorn(std::array::from_fn(|i| self[i] - point[i])) }
+    }
+
+    // Parses comma-separated coordinates, optionally in parentheses, e.g. (1, 2, 3, 4)
+    impl<const D: usize> FromStr for PointN<D> {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            let inner = s.trim();
+            let inner = inner.strip_prefix('(').and_then(|i| i.strip_suffix(')')).unwrap_or(inner);
+            let values = inner.split(',').map(|c| c.trim().parse()).collect::<Result<Vec<i32>, _>>()?;
+            ensure!(values.len() == D, "Invalid point '{}', expected {} coordinates", s, D);
+            Ok(pointn(values.try_into().expect("Length checked")))
+        }
+    }
+
+    impl<const D: usize> fmt::Debug for PointN<D> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let coords: Vec<_> = self.coords.iter().map(|c| c.to_string()).collect();
+            write!(f, "({})", coords.join(", "))
+        }
+    }
+
+    impl<const D: usize> fmt::Display for PointN<D> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    impl From<euclid::Point> for PointN<2> {
+        fn from(p: euclid::Point) -> Self { pointn([p.x, p.y]) }
+    }
+
+    impl From<PointN<2>> for euclid::Point {
+        fn from(p: PointN<2>) -> Self { euclid::point(p[0], p[1]) }
+    }
+
+    impl From<euclid3d::Point> for PointN<3> {
+        fn from(p: euclid3d::Point) -> Self { pointn([p.x, p.y, p.z]) }
+    }
+
+    impl From<PointN<3>> for euclid3d::Point {
+        fn from(p: PointN<3>) -> Self { euclid3d::point(p[0], p[1], p[2]) }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn parse() {
+            assert_eq!("3, 4, 5, 6".parse::<PointN<4>>().unwrap(), pointn([3, 4, 5, 6]));
+            assert_eq!("(-3,-4)".parse::<PointN<2>>().unwrap(), pointn([-3, -4]));
+            assert_eq!("7".parse::<PointN<1>>().unwrap(), pointn([7]));
+
+            assert!("abc".parse::<PointN<2>>().is_err());
+            assert!("(1, 2)".parse::<PointN<3>>().is_err());
+            assert!("1, 2, 3".parse::<PointN<2>>().is_err());
+            assert_eq!(pointn([1, -2, 3]).to_string(), "(1, -2, 3)");
+        }
+
+        #[test]
+        fn neighbors() {
+            assert_eq!(pointn([1, 1]).neighbors().count(), 8);
+            assert_eq!(pointn([1, 1, 1, 1]).neighbors().count(), 80);
+            assert!(pointn([0, 0, 0, 0]).neighbors().all(|n| n != PointN::ORIGIN && (n - PointN::ORIGIN).grid_len() <= 4));
+        }
+
+        #[test]
+        fn conversions() {
+            let p = euclid::point(3, -4);
+            assert_eq!(euclid::Point::from(PointN::from(p)), p);
+            let p = euclid3d::point(3, -4, 5);
+            assert_eq!(PointN::from(p), pointn([3, -4, 5]));
+            assert_eq!(euclid3d::Point::from(PointN::from(p)), p);
+        }
+
+        #[test]
+        fn add() {
+            assert_eq!(pointn([1, 0, 2, 5]) + vectorn([2, 3, 1, -5]), pointn([3, 3, 3, 0]));
+        }
+        #[test]
+        fn sub() {
+            assert_eq!(pointn([3, 3, 3, 0]) - pointn([1, 0, 2, 5]), vectorn([2, 3, 1, -5]));
+        }
+    }
+}
+pub use self::point::{PointN,pointn};
+
+mod bounds {
+    use super::*;
+
+    #[derive(Copy, Clone, Debug, PartialOrd, Ord, PartialEq, Eq, Hash)]
+    pub struct BoundsN<const D: usize> {
+        pub min: PointN<D>,
+        pub max: PointN<D>,
+    }
+
+    #[inline]
+    pub fn boundsn<const D: usize>(min: PointN<D>, max: PointN<D>) -> BoundsN<D> {
+        assert!((0..D).all(|i| min[i] <= max[i]), "{} is not below {}", min, max);
+        BoundsN{ min, max }
+    }
+
+    impl<const D: usize> BoundsN<D> {
+        pub fn from_points<'a>(points: impl IntoIterator<Item = &'a PointN<D>>) -> Option<BoundsN<D>> {
+            let bound: Option<(PointN<D>, PointN<D>)> = points.into_iter().fold(None, |r, c|
+                match r {
+                    Some((min, max)) => Some((
+                        pointn(std::array::from_fn(|i| cmp::min(min[i], c[i]))),
+                        pointn(std::array::from_fn(|i| cmp::max(max[i], c[i]))))),
+                    None => Some((*c, *c)),
+                }
+            );
+            bound.map(|(min, max)| boundsn(min, max))
+        }
+
+        pub fn contains(&self, pos: PointN<D>) -> bool {
+            (0..D).all(|i| self.min[i] <= pos[i] && pos[i] <= self.max[i])
+        }
+
+        // The number of points in the bounds
+        pub fn volume(&self) -> i64 {
+            (0..D).map(|i| (self.max[i] - self.min[i] + 1) as i64).product()
+        }
+
+        // Grows the bounds by margin in every direction
+        pub fn expand(&self, margin: i32) -> BoundsN<D> {
+            assert!(margin >= 0, "Cannot expand by a negative margin");
+            boundsn(self.min + vectorn([-margin; D]), self.max + vectorn([margin; D]))
+        }
+
+        // Every point in the bounds, varying the first coordinate fastest
+        pub fn iter(&self) -> impl Iterator<Item = PointN<D>> {
+            let bounds = *self;
+            std::iter::successors(Some(bounds.min), move |p| {
+                let mut next = *p;
+                for i in 0..D {
+                    if next[i] < bounds.max[i] {
+                        next[i] += 1;
+                        return Some(next);
+                    }
+                    next[i] = bounds.min[i];
+                }
+                None
+            })
+        }
+    }
+
+    impl From<euclid::Bounds> for BoundsN<2> {
+        fn from(b: euclid::Bounds) -> Self { boundsn(b.min.into(), b.max.into()) }
+    }
+
+    impl From<BoundsN<2>> for euclid::Bounds {
+        fn from(b: BoundsN<2>) -> Self { euclid::bounds(b.min.into(), b.max.into()) }
+    }
+
+    impl From<euclid3d::Bounds> for BoundsN<3> {
+        fn from(b: euclid3d::Bounds) -> Self { boundsn(b.min.into(), b.max.into()) }
+    }
+
+    impl From<BoundsN<3>> for euclid3d::Bounds {
+        fn from(b: BoundsN<3>) -> Self { euclid3d::bounds(b.min.into(), b.max.into()) }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn bounding() {
+            let points = [pointn([1, 2, 3, 4]), pointn([2, 3, 4, -1]), pointn([0, 5, 3, 0])];
+            let bound = BoundsN::from_points(&points).unwrap();
+            assert_eq!(bound, boundsn(pointn([0, 2, 3, -1]), pointn([2, 5, 4, 4])));
+            assert!(points.iter().all(|p| bound.contains(*p)));
+            assert!(!bound.contains(pointn([0, 2, 3, 5])));
+            assert_eq!(BoundsN::<4>::from_points(&[]), None);
+        }
+
+        #[test]
+        fn iter() {
+            let bound = boundsn(pointn([0, 0, 0, 0]), pointn([1, 2, 0, 3]));
+            let points: Vec<_> = bound.iter().collect();
+            assert_eq!(points.len() as i64, bound.volume());
+            assert_eq!(points.len(), 24);
+            assert_eq!(points[..3], [pointn([0, 0, 0, 0]), pointn([1, 0, 0, 0]), pointn([0, 1, 0, 0])]);
+            assert_eq!(points.last(), Some(&bound.max));
+            assert!(points.iter().all(|p| bound.contains(*p)));
+
+            assert_eq!(bound.expand(1).volume(), 4 * 5 * 3 * 6);
+        }
+
+        #[test]
+        fn conversions() {
+            let b = euclid::bounds(euclid::point(0, 1), euclid::point(3, 4));
+            assert_eq!(BoundsN::from(b).iter().map(euclid::Point::from).collect::<Vec<_>>(), b.iter().collect::<Vec<_>>());
+            let b = euclid3d::bounds(euclid3d::point(0, 1, 2), euclid3d::point(3, 4, 5));
+            assert_eq!(BoundsN::from(b).iter().map(euclid3d::Point::from).collect::<Vec<_>>(), b.iter().collect::<Vec<_>>());
+            assert_eq!(euclid3d::Bounds::from(BoundsN::from(b)), b);
+        }
+    }
+}
+pub use self::bounds::{BoundsN,boundsn};
+
+mod vector {
+    use super::*;
+
+    #[derive(Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
+    pub struct VectorN<const D: usize> {
+        pub coords: [i32; D],
+    }
+
+    #[inline]
+    pub const fn vectorn<const D: usize>(coords: [i32; D]) -> VectorN<D> {
+        VectorN { coords }
+    }
+
+    impl<const D: usize> VectorN<D> {
+        pub const ZERO: VectorN<D> = vectorn([0; D]);
+
+        // All 3^D-1 unit offsets, including diagonals
+        pub fn neighbors() -> impl Iterator<Item = VectorN<D>> {
+            (0..3_usize.pow(D as u32))
+                .map(|n| vectorn(std::array::from_fn(|i| (n / 3_usize.pow(i as u32) % 3) as i32 - 1)))
+                .filter(|v| *v != VectorN::ZERO)
+        }
+
+        pub fn abs(&self) -> VectorN<D> {
+            vectorn(self.coords.map(|c| c.abs()))
+        }
+
+        pub fn len(&self) -> f64 {
+            self.coords.iter().map(|&c| c as f64 * c as f64).sum::<f64>().sqrt()
+        }
+
+        pub fn grid_len(&self) -> u32 {
+            self.coords.iter().map(|c| c.unsigned_abs()).sum()
+        }
+    }
+
+    impl<const D: usize> Index<usize> for VectorN<D> {
+        type Output = i32;
+
+        fn index(&self, axis: usize) -> &i32 { &self.coords[axis] }
+    }
+
+    impl<const D: usize> Add for VectorN<D> {
+        type Output = VectorN<D>;
+
+        fn add(self, other: VectorN<D>) -> VectorN<D> { vectorn(std::array::from_fn(|i| self[i] + other[i])) }
+    }
+
+    impl<const D: usize> Mul<i32> for VectorN<D> {
+        type Output = VectorN<D>;
+
+        fn mul(self, n: i32) -> VectorN<D> { vectorn(self.coords.map(|c| c * n)) }
+    }
+
+    impl<const D: usize> FromStr for VectorN<D> {
+        type Err = Error;
+
+        fn from_str(s: &str) -> Result<Self> {
+            // Just reuse point's parser
+            let p: super::PointN<D> = s.parse()?;
+            Ok(vectorn(p.coords))
+        }
+    }
+
+    impl<const D: usize> fmt::Debug for VectorN<D> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", pointn(self.coords))
+        }
+    }
+
+    impl<const D: usize> fmt::Display for VectorN<D> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    impl From<euclid::Vector> for VectorN<2> {
+        fn from(v: euclid::Vector) -> Self { vectorn([v.x, v.y]) }
+    }
+
+    impl From<VectorN<2>> for euclid::Vector {
+        fn from(v: VectorN<2>) -> Self { euclid::vector(v[0], v[1]) }
+    }
+
+    impl From<euclid3d::Vector> for VectorN<3> {
+        fn from(v: euclid3d::Vector) -> Self { vectorn([v.x, v.y, v.z]) }
+    }
+
+    impl From<VectorN<3>> for euclid3d::Vector {
+        fn from(v: VectorN<3>) -> Self { euclid3d::vector(v[0], v[1], v[2]) }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::super::pointn;
+        use super::*;
+        use std::collections::HashSet;
+        use assert_approx_eq::assert_approx_eq;
+
+        #[test]
+        fn parse() {
+            assert_eq!("3, 4, 5, 6".parse::<VectorN<4>>().unwrap(), vectorn([3, 4, 5, 6]));
+            assert_eq!("-3,-4".parse::<VectorN<2>>().unwrap(), vectorn([-3, -4]));
+        }
+
+        #[test]
+        fn neighbors() {
+            let two: HashSet<_> = VectorN::<2>::neighbors().map(euclid::Vector::from).collect();
+            assert_eq!(two, euclid::Vector::ORDINAL.iter().copied().collect());
+            let three: Vec<_> = VectorN::<3>::neighbors().collect();
+            assert_eq!(three.len(), 26);
+            assert!(euclid3d::Vector::CARDINAL.iter().all(|v| three.contains(&VectorN::from(*v))));
+            assert_eq!(VectorN::<4>::neighbors().collect::<HashSet<_>>().len(), 80);
+            assert_eq!(VectorN::<0>::neighbors().count(), 0);
+        }
+
+        parameterized_test::create!{ lens, (p1, p2, d), {
+            assert_approx_eq!((p1 - p2).len(), d, f64::EPSILON);
+            assert_approx_eq!((p2 - p1).len(), d, f64::EPSILON);
+        }}
+        lens! {
+            a: (pointn([1,1,1,1]), pointn([1,1,1,1]), 0.0),
+            b: (pointn([1,1,1,1]), pointn([1,2,1,1]), 1.0),
+            c: (pointn([1,1,1,1]), pointn([2,2,2,2]), 2.0),
+            d: (pointn([1,1,1]), pointn([8,3,5]), 8.306623862918075),
+        }
+
+        parameterized_test::create!{ grid_lens, (p1, p2, d), {
+            assert_eq!((p1 - p2).grid_len(), d);
+            assert_eq!((p2 - p1).grid_len(), d);
+        }}
+        grid_lens! {
+            a: (pointn([1,1,1,1]), pointn([1,1,1,1]), 0),
+            b: (pointn([1,1,1,1]), pointn([1,2,1,1]), 1),
+            c: (pointn([1,1,1,1]), pointn([2,2,2,2]), 4),
+            d: (pointn([1,1,1,1]), pointn([8,3,5,-1]), 15),
+        }
+
+        #[test]
+        fn conversions() {
+            assert_eq!(euclid::Vector::from(VectorN::from(euclid::vector(1, -2))), euclid::vector(1, -2));
+            assert_eq!(euclid3d::Vector::from(vectorn([1, -2, 3])), euclid3d::vector(1, -2, 3));
+            assert_eq!(vectorn([1, -2, 3]) * 2 + vectorn([0, 0, 1]), vectorn([2, -4, 7]));
+            assert_eq!(vectorn([1, -2, 3]).abs(), vectorn([1, 2, 3]));
+        }
+    }
+}
+pub use self::vector::{VectorN,vectorn};
@@ -0,0 +1,110 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+
+// If set, inputs are read from $ADVENT_INPUT_DIR/NN.txt when present, e.g. to run the solutions
+// against a different account's inputs without recompiling.
+pub const INPUT_DIR_ENV: &str = "ADVENT_INPUT_DIR";
+
+// Where a day's puzzle input should be read from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+    Embedded,
+}
+
+impl Source {
+    // Determines the source for a day's input; an explicit path takes precedence over the input
+    // directory, and "-" means stdin. If neither applies the embedded input is used.
+    pub fn resolve(day: u32, path: Option<&str>, input_dir: Option<&Path>) -> Source {
+        match path {
+            Some("-") => Source::Stdin,
+            Some(path) => Source::File(PathBuf::from(path)),
+            None => input_dir
+                .map(|dir| dir.join(format!("{:02}.txt", day)))
+                .filter(|p| p.is_file())
+                .map(Source::File)
+                .unwrap_or(Source::Embedded),
+        }
+    }
+
+    pub fn read(&self, embedded: &'static str) -> Result<Cow<'static, str>> {
+        match self {
+            Source::File(path) => {
+                let input = std::fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read input from {}", path.display()))?;
+                Ok(Cow::Owned(input))
+            },
+            Source::Stdin => {
+                let mut input = String::new();
+                std::io::stdin().read_to_string(&mut input).context("Failed to read input from stdin")?;
+                Ok(Cow::Owned(input))
+            },
+            Source::Embedded => Ok(Cow::Borrowed(embedded)),
+        }
+    }
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::File(path) => write!(f, "{}", path.display()),
+            Source::Stdin => write!(f, "stdin"),
+            Source::Embedded => write!(f, "embedded input"),
+        }
+    }
+}
+
+// Loads a day's input from the given path, $ADVENT_INPUT_DIR, or the embedded input, in that order.
+pub fn load(day: u32, path: Option<&str>, embedded: &'static str) -> Result<Cow<'static, str>> {
+    let input_dir = std::env::var_os(INPUT_DIR_ENV).map(PathBuf::from);
+    Source::resolve(day, path, input_dir.as_deref()).read(embedded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("advent-input-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn explicit_path() {
+        assert_eq!(Source::resolve(1, Some("-"), None), Source::Stdin);
+        assert_eq!(Source::resolve(1, Some("foo.txt"), Some(Path::new("/bar"))), Source::File(PathBuf::from("foo.txt")));
+    }
+
+    #[test]
+    fn input_dir() {
+        let dir = temp_dir("dir");
+        std::fs::write(dir.join("03.txt"), "three\n").unwrap();
+
+        let source = Source::resolve(3, None, Some(&dir));
+        assert_eq!(source, Source::File(dir.join("03.txt")));
+        assert_eq!(source.read("embedded").unwrap(), "three\n");
+
+        // Days missing from the directory fall back to the embedded input
+        let source = Source::resolve(4, None, Some(&dir));
+        assert_eq!(source, Source::Embedded);
+        assert_eq!(source.read("embedded").unwrap(), "embedded");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn embedded() {
+        assert_eq!(Source::resolve(1, None, None).read("embedded").unwrap(), "embedded");
+    }
+
+    #[test]
+    fn missing_file() {
+        let err = Source::File(PathBuf::from("/does/not/exist.txt")).read("").unwrap_err();
+        assert!(err.to_string().contains("/does/not/exist.txt"), "{}", err);
+    }
+}
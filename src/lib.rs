@@ -2,8 +2,16 @@ extern crate lazy_static;
 extern crate regex;
 extern crate anyhow;
 
+pub mod answers;
+pub mod bench;
+pub mod cache;
 pub mod collect;
 pub mod euclid3d;
 pub mod euclid;
+pub mod euclidn;
+pub mod input;
 pub mod pathfinding;
+pub mod scaffold;
+pub mod solution;
 pub mod terminal;
+pub mod timing;
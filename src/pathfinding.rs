@@ -401,7 +401,7 @@ pub use self::internal::{Edge,Graph,NodeGraph};
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::euclid::{point, Point, Vector};
+    use crate::euclid::{axial, point, Axial, Point, Vector};
     use std::collections::{BTreeMap, HashSet};
     use std::rc::Rc;
     use ahash::{AHashMap, AHashSet};
@@ -447,7 +447,7 @@ mod tests {
         assert_eq!(djk_route[0].source(), &start);
         assert_eq!(djk_route[djk_route.len()-1].dest(), &goal);
 
-        let as_route = graph.a_star(&start, |n| n == &goal, |n| (goal - *n).grid_len() as i32).unwrap();
+        let as_route = graph.a_star(&start, |n| n == &goal, |n| (goal - *n).grid_len()).unwrap();
         assert_eq!(as_route.len(), 5);
         assert_eq!(as_route[0].source(), &start);
         assert_eq!(as_route[djk_route.len()-1].dest(), &goal);
@@ -471,7 +471,7 @@ mod tests {
         assert_eq!(djk_route[0].source(), &start);
         assert_eq!(djk_route[djk_route.len()-1].dest(), &goal);
 
-        let as_route = graph.a_star(&start, |n| n == &goal, |n| (goal - *n).grid_len() as i32).unwrap();
+        let as_route = graph.a_star(&start, |n| n == &goal, |n| (goal - *n).grid_len()).unwrap();
         assert_eq!(as_route.len(), 9);
         assert_eq!(as_route[0].source(), &start);
         assert_eq!(as_route[djk_route.len()-1].dest(), &goal);
@@ -511,6 +511,37 @@ mod tests {
         assert_eq!(&bfs_route, bfs_all_route);
     }
 
+    struct HexGraph {
+        blocked: AHashSet<Axial>,
+    }
+
+    impl Graph for HexGraph {
+        type Node = Axial;
+
+        fn neighbors(&self, source: &Self::Node) -> Vec<Edge<Self::Node>> {
+            source.neighbors().into_iter()
+                .filter(|n| !self.blocked.contains(n))
+                .map(|d| Edge::new(1, *source, d))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn hex() {
+        let start = Axial::ORIGIN;
+        let goal = axial(3, -1);
+        let open = HexGraph{ blocked: AHashSet::new() };
+        assert_eq!(open.bfs(&start, |n| n == &goal).unwrap().len() as i32, start.distance(&goal) + 1);
+
+        // A wall between the start and the goal
+        let walled = HexGraph{ blocked: (-3..=2).map(|r| axial(1, r)).collect() };
+        let bfs_route = walled.bfs(&start, |n| n == &goal).unwrap();
+        assert_eq!(bfs_route.len(), 9);
+        let as_route = walled.a_star(&start, |n| n == &goal, |n| n.distance(&goal)).unwrap();
+        assert_eq!(as_route.len(), bfs_route.len() - 1);
+        assert_eq!(as_route[as_route.len()-1].dest(), &goal);
+    }
+
     struct RcGraph {
         edges: AHashMap<Rc<str>, Vec<(Rc<str>, i32)>>,
     }
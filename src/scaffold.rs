@@ -0,0 +1,317 @@
+// Creates the skeleton for a new day from src/bin/template, downloads its input, extracts the
+// puzzle's examples, and registers it with the advent runner and answers.txt.
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use anyhow::{bail, ensure, Context, Result};
+use lazy_regex::regex;
+
+use crate::cache::{self, InputCache};
+
+pub const YEAR: u32 = 2023;
+pub const BASE_URL: &str = "https://adventofcode.com";
+const USER_AGENT: &str = "curl: https://github.com/dimo414/advent-2023";
+
+// Retrieves the contents of a URL, so that tests can fetch from a local server instead.
+pub trait Fetcher {
+    fn fetch(&self, url: &str) -> Result<String>;
+}
+
+// Fetches URLs with curl, authenticating with the session cookie in a Netscape-style cookie jar.
+pub struct Curl {
+    cookie_jar: PathBuf,
+}
+
+impl Curl {
+    // Creates a cookie jar at the given path if needed; the session cookie must be added by hand.
+    pub fn create(cookie_jar: impl Into<PathBuf>) -> Result<Curl> {
+        let cookie_jar = cookie_jar.into();
+        if !cookie_jar.exists() {
+            std::fs::write(&cookie_jar, "# https://curl.se/docs/http-cookies.html\n.adventofcode.com\tTRUE\t/\tFALSE\t0\tsession\t")
+                .with_context(|| format!("Failed to create {}", cookie_jar.display()))?;
+        }
+        Ok(Curl{ cookie_jar })
+    }
+
+    // True if the cookie jar still lacks a session cookie
+    pub fn missing_session(&self) -> bool {
+        std::fs::read_to_string(&self.cookie_jar).map(|c| c.trim_end_matches('\n').ends_with('\t')).unwrap_or(true)
+    }
+}
+
+impl Fetcher for Curl {
+    fn fetch(&self, url: &str) -> Result<String> {
+        let res = Command::new("curl")
+            .args(["-A", USER_AGENT, "--fail", "--silent", "--show-error", "--cookie"])
+            .arg(&self.cookie_jar)
+            .arg(url)
+            .output().context("Failed to run curl")?;
+        ensure!(res.status.success(), "Failed to fetch {}: {}", url, String::from_utf8_lossy(&res.stderr).trim());
+        Ok(String::from_utf8(res.stdout)?)
+    }
+}
+
+pub fn puzzle_url(base_url: &str, day: u32) -> String {
+    format!("{}/{}/day/{}", base_url, YEAR, day)
+}
+
+pub fn input_url(base_url: &str, day: u32) -> String {
+    format!("{}/input", puzzle_url(base_url, day))
+}
+
+// Returns the contents of the <pre><code> blocks in a puzzle's HTML, which are generally the
+// examples. Formatting tags such as <em> are removed.
+pub fn extract_examples(html: &str) -> Vec<String> {
+    regex!(r"(?s)<pre><code>(.*?)</code></pre>").captures_iter(html)
+        .map(|c| {
+            let text = unescape_html(&regex!(r"<[^>]*>").replace_all(&c[1], ""));
+            if text.ends_with('\n') { text } else { text + "\n" }
+        })
+        .collect()
+}
+
+fn unescape_html(s: &str) -> String {
+    // &amp; must be last so that e.g. &amp;lt; becomes &lt; rather than <
+    s.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&#39;", "'").replace("&amp;", "&")
+}
+
+// A lone example is written to example.txt, multiple examples to example1.txt, example2.txt, etc.
+fn example_names(count: usize) -> Vec<String> {
+    if count == 1 { return vec!["example.txt".into()]; }
+    (1..=count).map(|i| format!("example{}.txt", i)).collect()
+}
+
+// Inserts line into source, ordered by day among the lines matching key(d) for other days. Lines
+// match if they're equal to key(d) or start with key(d) followed by a tab. Returns false if a
+// line for the day already exists.
+fn insert_line(source: &mut String, day: u32, key: impl Fn(u32) -> String, line: &str) -> Result<bool> {
+    let line_day = |l: &str| (1..=25).find(|&d| l.split('\t').next() == Some(key(d).as_str()));
+    let mut lines: Vec<_> = source.lines().collect();
+    let days: Vec<_> = lines.iter().enumerate().filter_map(|(i, l)| line_day(l).map(|d| (i, d))).collect();
+    ensure!(!days.is_empty(), "No lines like '{}' found", key(day));
+    if days.iter().any(|&(_, d)| d == day) { return Ok(false); }
+    let index = days.iter().find(|&&(_, d)| d > day).map(|&(i, _)| i)
+        .unwrap_or_else(|| days.last().expect("Non-empty").0 + 1);
+    lines.insert(index, line);
+    *source = lines.join("\n") + "\n";
+    Ok(true)
+}
+
+pub struct Scaffold {
+    root: PathBuf,
+    day: u32,
+}
+
+impl Scaffold {
+    // root is the repository root, i.e. the directory containing src/
+    pub fn new(root: impl Into<PathBuf>, day: u32) -> Result<Scaffold> {
+        ensure!((1..=25).contains(&day), "Invalid day: {}", day);
+        Ok(Scaffold{ root: root.into(), day })
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        self.root.join(format!("src/bin/{:02}", self.day))
+    }
+
+    fn template(&self) -> PathBuf {
+        self.root.join("src/bin/template")
+    }
+
+    // Copies the template into the day's directory; returns false if the directory already exists.
+    pub fn create(&self) -> Result<bool> {
+        let dir = self.dir();
+        if dir.exists() { return Ok(false); }
+        std::fs::create_dir(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        for file in std::fs::read_dir(self.template())? {
+            let file = file?;
+            let dest = dir.join(file.file_name());
+            if file.file_name() == "main.rs" {
+                let main = std::fs::read_to_string(file.path())?;
+                ensure!(main.contains("const DAY: u32 = 0;"), "Template is missing 'const DAY: u32 = 0;'");
+                std::fs::write(dest, main.replace("const DAY: u32 = 0;", &format!("const DAY: u32 = {};", self.day)))?;
+            } else {
+                std::fs::copy(file.path(), dest)?;
+            }
+        }
+        Ok(true)
+    }
+
+    // Installs the day's input from the cache, fetching it first if necessary. See InputCache::fetch().
+    pub fn fetch_input(&self, cache: &InputCache, fetcher: &dyn Fetcher, base_url: &str, refresh: bool) -> Result<()> {
+        let input = cache.fetch(self.day, fetcher, &input_url(base_url, self.day), refresh)?;
+        cache::install(&input, &self.dir().join("input.txt"))
+    }
+
+    pub fn fetch_examples(&self, fetcher: &dyn Fetcher, base_url: &str) -> Result<Vec<PathBuf>> {
+        self.write_examples(&fetcher.fetch(&puzzle_url(base_url, self.day))?)
+    }
+
+    // Writes the examples in the puzzle's HTML to the day's directory, replacing the template's
+    // placeholder example.txt. Existing examples are not overwritten.
+    pub fn write_examples(&self, html: &str) -> Result<Vec<PathBuf>> {
+        let examples = extract_examples(html);
+        if examples.is_empty() { bail!("No examples found"); }
+
+        let placeholder = self.dir().join("example.txt");
+        if placeholder.exists() && std::fs::read(&placeholder).ok() == std::fs::read(self.template().join("example.txt")).ok() {
+            std::fs::remove_file(&placeholder)?;
+        }
+        let mut written = Vec::new();
+        for (name, example) in example_names(examples.len()).iter().zip(examples) {
+            let path = self.dir().join(name);
+            if path.exists() { continue; }
+            std::fs::write(&path, example)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    // Adds the day to the advent runner and answers.txt, if it isn't already present.
+    pub fn register(&self) -> Result<()> {
+        fn update(path: &Path, edit: impl FnOnce(&mut String) -> Result<bool>) -> Result<()> {
+            let mut source = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+            if edit(&mut source).with_context(|| format!("Failed to update {}", path.display()))? {
+                std::fs::write(path, source)?;
+            }
+            Ok(())
+        }
+
+        let day = self.day;
+        update(&self.root.join("src/bin/advent.rs"), |source| {
+            let module = |d| format!(r#"#[allow(dead_code)] #[path = "{0:02}/main.rs"] mod day{0:02};"#, d);
+            let entry = |d| format!("        Entry::of::<day{:02}::Day>(),", d);
+            Ok(insert_line(source, day, module, &module(day))? | insert_line(source, day, entry, &entry(day))?)
+        })?;
+        update(&self.root.join("answers.txt"), |source| {
+            let key = |d| format!("{:02}", d);
+            insert_line(source, day, key, &key(day))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::Write;
+
+    const PUZZLE: &str = "<article class=\"day-desc\"><p>For example:</p>\n\
+        <pre><code>1 &lt; 2\n<em>three</em> &amp; four\n</code></pre>\n<p>Or:</p>\n\
+        <pre><code>five</code></pre></article>";
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("advent-scaffold-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("src/bin/template")).unwrap();
+        dir
+    }
+
+    // Serves fixed pages by URL, failing for any other URL.
+    struct StubFetcher {
+        pages: HashMap<String, &'static str>,
+    }
+
+    impl StubFetcher {
+        fn new(pages: &[(String, &'static str)]) -> StubFetcher {
+            StubFetcher{ pages: pages.iter().cloned().collect() }
+        }
+    }
+
+    impl Fetcher for StubFetcher {
+        fn fetch(&self, url: &str) -> Result<String> {
+            self.pages.get(url).map(|p| p.to_string()).with_context(|| format!("Not found: {}", url))
+        }
+    }
+
+    #[test]
+    fn examples() {
+        assert_eq!(extract_examples(PUZZLE), ["1 < 2\nthree & four\n", "five\n"]);
+        assert_eq!(extract_examples("<p>No examples</p>"), Vec::<String>::new());
+        assert_eq!(example_names(1), ["example.txt"]);
+        assert_eq!(example_names(2), ["example1.txt", "example2.txt"]);
+    }
+
+    parameterized_test::create!{ insert, (source, expected), {
+        let mut updated = String::from(source);
+        let changed = insert_line(&mut updated, 5, |d| format!("{:02}", d), "05").unwrap();
+        assert_eq!(updated, expected);
+        assert_eq!(changed, source != expected);
+    }}
+    insert! {
+        middle: ("# header\n03\ta\n07\tb\n", "# header\n03\ta\n05\n07\tb\n"),
+        first: ("# header\n07\tb\n", "# header\n05\n07\tb\n"),
+        last: ("03\ta\n# footer\n", "03\ta\n05\n# footer\n"),
+        present: ("05\ta\n", "05\ta\n"),
+    }
+
+    #[test]
+    fn insert_without_matches() {
+        assert!(insert_line(&mut String::from("foo\n"), 5, |d| d.to_string(), "5").is_err());
+    }
+
+    #[test]
+    fn scaffold() {
+        let root = temp_dir("scaffold");
+        std::fs::write(root.join("src/bin/template/main.rs"), "const DAY: u32 = 0;\n").unwrap();
+        std::fs::write(root.join("src/bin/template/example.txt"), "placeholder\n").unwrap();
+        std::fs::write(root.join("src/bin/template/input.txt"), "placeholder\n").unwrap();
+        std::fs::write(root.join("src/bin/advent.rs"), concat!(
+            "#[allow(dead_code)] #[path = \"01/main.rs\"] mod day01;\n",
+            "#[allow(dead_code)] #[path = \"25/main.rs\"] mod day25;\n",
+            "fn registry() -> Vec<Entry> {\n",
+            "    vec![\n",
+            "        Entry::of::<day01::Day>(),\n",
+            "        Entry::of::<day25::Day>(),\n",
+            "    ]\n",
+            "}\n")).unwrap();
+        std::fs::write(root.join("answers.txt"), "# Day\tPart 1\tPart 2\n01\t1\t2\n25\t3\n").unwrap();
+
+        let scaffold = Scaffold::new(&root, 7).unwrap();
+        assert!(scaffold.create().unwrap());
+        assert!(!scaffold.create().unwrap());
+        assert_eq!(std::fs::read_to_string(scaffold.dir().join("main.rs")).unwrap(), "const DAY: u32 = 7;\n");
+
+        let base_url = "https://adventofcode.test";
+        let fetcher = StubFetcher::new(&[(input_url(base_url, 7), "Some input\n"), (puzzle_url(base_url, 7), PUZZLE)]);
+        let cache = InputCache::new(root.join(".input-cache"), YEAR, cache::DEFAULT_COOLDOWN);
+        scaffold.fetch_input(&cache, &fetcher, base_url, false).unwrap();
+        assert_eq!(std::fs::read_to_string(scaffold.dir().join("input.txt")).unwrap(), "Some input\n");
+        // Served from the cache, since the cooldown prevents re-fetching
+        std::fs::remove_file(scaffold.dir().join("input.txt")).unwrap();
+        scaffold.fetch_input(&cache, &StubFetcher::new(&[]), base_url, false).unwrap();
+        assert_eq!(std::fs::read_to_string(scaffold.dir().join("input.txt")).unwrap(), "Some input\n");
+        assert!(scaffold.fetch_input(&cache, &fetcher, base_url, true).is_err());
+
+        let written = scaffold.fetch_examples(&fetcher, base_url).unwrap();
+        assert_eq!(written, [scaffold.dir().join("example1.txt"), scaffold.dir().join("example2.txt")]);
+        assert!(!scaffold.dir().join("example.txt").exists());
+        assert_eq!(std::fs::read_to_string(scaffold.dir().join("example2.txt")).unwrap(), "five\n");
+
+        scaffold.register().unwrap();
+        scaffold.register().unwrap();
+        assert_eq!(std::fs::read_to_string(root.join("src/bin/advent.rs")).unwrap(), concat!(
+            "#[allow(dead_code)] #[path = \"01/main.rs\"] mod day01;\n",
+            "#[allow(dead_code)] #[path = \"07/main.rs\"] mod day07;\n",
+            "#[allow(dead_code)] #[path = \"25/main.rs\"] mod day25;\n",
+            "fn registry() -> Vec<Entry> {\n",
+            "    vec![\n",
+            "        Entry::of::<day01::Day>(),\n",
+            "        Entry::of::<day07::Day>(),\n",
+            "        Entry::of::<day25::Day>(),\n",
+            "    ]\n",
+            "}\n"));
+        assert_eq!(std::fs::read_to_string(root.join("answers.txt")).unwrap(), "# Day\tPart 1\tPart 2\n01\t1\t2\n07\n25\t3\n");
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn missing_session() {
+        let root = temp_dir("session");
+        let curl = Curl::create(root.join(".cookie-jar")).unwrap();
+        assert!(curl.missing_session());
+        let mut jar = std::fs::OpenOptions::new().append(true).open(root.join(".cookie-jar")).unwrap();
+        jar.write_all(b"abc123\n").unwrap();
+        assert!(!curl.missing_session());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,272 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use anyhow::{bail, ensure, Error, Result};
+
+use crate::answers::{self, Outcome};
+use crate::bench::{self, Config, Report};
+use crate::elapsed;
+use crate::input;
+use crate::timing;
+
+// Each day implements this trait so that its binary and the `advent` runner can share the same
+// parse/part1/part2 hooks. Parts return Strings so that answers can be printed and compared
+// uniformly regardless of their underlying type.
+pub trait Solution {
+    const DAY: u32;
+    // The embedded puzzle input, generally include_str!("input.txt"). See input::load() for
+    // alternatives that don't require recompiling.
+    const INPUT: &'static str;
+    // Day 25 only has one puzzle
+    const PART2: bool = true;
+
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<String>;
+
+    fn part2(_input: &Self::Input) -> Result<String> {
+        unreachable!("Day {} does not have a part 2", Self::DAY)
+    }
+
+    // Other implementations of the parts (e.g. brute-force approaches), which are benchmarked
+    // alongside the primary implementations. See bench::bench().
+    fn alternatives() -> Vec<Alternative<Self::Input>> {
+        Vec::new()
+    }
+}
+
+// A named, alternative strategy for solving one part of a day.
+pub struct Alternative<I> {
+    pub part: Part,
+    pub name: &'static str,
+    pub solve: fn(&I) -> Result<String>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: &'static [Part] = &[Part::One, Part::Two];
+
+    pub fn solve<S: Solution>(&self, input: &S::Input) -> Result<String> {
+        match self {
+            Part::One => S::part1(input),
+            Part::Two => S::part2(input),
+        }
+    }
+
+    pub fn exists_for<S: Solution>(&self) -> bool {
+        *self == Part::One || S::PART2
+    }
+}
+
+impl FromStr for Part {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => bail!("Invalid part '{}', expected 1 or 2", s),
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "Part 1"),
+            Part::Two => write!(f, "Part 2"),
+        }
+    }
+}
+
+// Parses the input and prints the answer to each part; if part is None all parts are run.
+pub fn run_parts<S: Solution>(input: &str, part: Option<Part>) -> Result<()> {
+    let _span = timing_span(format!("Day {:02}", S::DAY));
+    let input = elapsed!("Parse", S::parse(input))?;
+    for p in Part::ALL.iter().filter(|p| part.is_none() || part == Some(**p)) {
+        if !p.exists_for::<S>() {
+            if part.is_some() { bail!("Day {} does not have a {}", S::DAY, p); }
+            continue;
+        }
+        println!("{}:\t{}", p, elapsed!(p.to_string(), p.solve::<S>(&input))?);
+    }
+    Ok(())
+}
+
+// elapsed!() can't wrap the early returns above, so the day's span is held as a guard
+fn timing_span(name: String) -> Option<timing::Guard> {
+    if cfg!(feature="timing") { Some(timing::span(&name)) } else { None }
+}
+
+// Entry-point for the individual day binaries; an input file (or - for stdin) can be passed as
+// the first argument.
+pub fn run<S: Solution>() -> Result<()> {
+    let input = input::load(S::DAY, std::env::args().nth(1).as_deref(), S::INPUT)?;
+    run_parts::<S>(&input, None)?;
+    timing::report()
+}
+
+// A type-erased Solution, so that days can be stored together in a registry.
+#[derive(Copy, Clone)]
+pub struct Entry {
+    day: u32,
+    part2: bool,
+    embedded: &'static str,
+    run: fn(&str, Option<Part>) -> Result<()>,
+    check: fn() -> Result<Vec<(Part, Outcome)>>,
+    bench: fn(&str, &Config) -> Result<Report>,
+}
+
+impl Entry {
+    pub fn of<S: Solution>() -> Entry {
+        Entry{ day: S::DAY, part2: S::PART2, embedded: S::INPUT, run: run_parts::<S>, check: answers::check::<S>, bench: bench::bench::<S> }
+    }
+
+    pub fn day(&self) -> u32 { self.day }
+
+    pub fn has_part(&self, part: Part) -> bool {
+        part == Part::One || self.part2
+    }
+
+    // See input::load()
+    pub fn load(&self, path: Option<&str>) -> Result<Cow<'static, str>> {
+        input::load(self.day, path, self.embedded)
+    }
+
+    pub fn run(&self, input: &str, part: Option<Part>) -> Result<()> {
+        (self.run)(input, part)
+    }
+
+    // See answers::check()
+    pub fn check(&self) -> Result<Vec<(Part, Outcome)>> {
+        (self.check)()
+    }
+
+    // See bench::bench()
+    pub fn bench(&self, input: &str, config: &Config) -> Result<Report> {
+        (self.bench)(input, config)
+    }
+}
+
+// Parses a comma-separated list of days or ranges of days, e.g. "1,3..5,7..=8" or "all".
+pub fn parse_days(spec: &str) -> Result<Vec<u32>> {
+    let mut days = Vec::new();
+    for part in spec.split(',').map(|p| p.trim()) {
+        let range = if part == "all" {
+            1..=25
+        } else if let Some((start, end)) = part.split_once("..=") {
+            start.parse()?..=end.parse()?
+        } else if let Some((start, end)) = part.split_once("..") {
+            let end: u32 = end.parse()?;
+            ensure!(end > 0, "Invalid range '{}'", part);
+            start.parse()?..=(end - 1)
+        } else {
+            let day = part.parse()?;
+            day..=day
+        };
+        ensure!(!range.is_empty(), "Empty range '{}'", part);
+        ensure!(*range.start() >= 1 && *range.end() <= 25, "Days must be between 1 and 25: '{}'", part);
+        days.extend(range);
+    }
+    days.sort();
+    days.dedup();
+    Ok(days)
+}
+
+impl fmt::Debug for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day {:02}", self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Example;
+
+    impl Solution for Example {
+        const DAY: u32 = 0;
+        const INPUT: &'static str = "2\n3\n4\n";
+
+        type Input = Vec<u32>;
+
+        fn parse(input: &str) -> Result<Self::Input> {
+            input.lines().map(|l| Ok(l.parse()?)).collect()
+        }
+
+        fn part1(input: &Self::Input) -> Result<String> {
+            Ok(input.iter().sum::<u32>().to_string())
+        }
+
+        fn part2(input: &Self::Input) -> Result<String> {
+            Ok(input.iter().product::<u32>().to_string())
+        }
+    }
+
+    struct OnePart;
+
+    impl Solution for OnePart {
+        const DAY: u32 = 25;
+        const INPUT: &'static str = "";
+        const PART2: bool = false;
+
+        type Input = ();
+
+        fn parse(_input: &str) -> Result<Self::Input> { Ok(()) }
+
+        fn part1(_input: &Self::Input) -> Result<String> { Ok("done".into()) }
+    }
+
+    #[test]
+    fn parts() {
+        assert_eq!("1".parse::<Part>().unwrap(), Part::One);
+        assert_eq!("2".parse::<Part>().unwrap(), Part::Two);
+        assert!("3".parse::<Part>().is_err());
+
+        let input = Example::parse(Example::INPUT).unwrap();
+        assert_eq!(Part::One.solve::<Example>(&input).unwrap(), "9");
+        assert_eq!(Part::Two.solve::<Example>(&input).unwrap(), "24");
+    }
+
+    parameterized_test::create!{ days, (spec, expected), {
+        assert_eq!(parse_days(spec).unwrap(), expected);
+    }}
+    days! {
+        one: ("17", vec![17]),
+        list: ("3,1,2,1", vec![1, 2, 3]),
+        exclusive: ("1..4", vec![1, 2, 3]),
+        inclusive: ("1..=4", vec![1, 2, 3, 4]),
+        mixed: ("25,1..=2,10..12", vec![1, 2, 10, 11, 25]),
+        all: ("all", (1..=25).collect::<Vec<_>>()),
+    }
+
+    parameterized_test::create!{ bad_days, spec, {
+        assert!(parse_days(spec).is_err());
+    }}
+    bad_days! {
+        zero: "0",
+        too_big: "26",
+        empty: "5..5",
+        reversed: "5..=3",
+        not_a_number: "abc",
+    }
+
+    #[test]
+    fn missing_part() {
+        assert!(Part::Two.exists_for::<Example>());
+        assert!(!Part::Two.exists_for::<OnePart>());
+        let entry = Entry::of::<OnePart>();
+        assert_eq!(entry.day(), 25);
+        assert!(!entry.has_part(Part::Two));
+        entry.run("", None).unwrap();
+        assert!(entry.run("", Some(Part::Two)).is_err());
+    }
+}
@@ -3,10 +3,9 @@
 macro_rules! elapsed {
     ($expression:expr) => { elapsed!(stringify!($expression), $expression) };
     ($desc:expr, $expression:expr) => { {
-        println!("\x1B[36m[{}...\x1B[0m", $desc);
-        let start = std::time::Instant::now();
+        let span = $crate::timing::span(&$desc);
         let ret = $expression;
-        println!("\x1B[36mElapsed: {:?}]\x1B[0m", start.elapsed());
+        drop(span);
         ret
     } }
 }
@@ -0,0 +1,277 @@
+// Records a tree of named, nested timing spans, e.g. via the elapsed!() macro when the timing
+// feature is enabled. Spans with the same name and parent are aggregated together.
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+// If set, report() writes the recorded spans to this path as JSON instead of printing a summary.
+pub const JSON_ENV: &str = "ADVENT_TIMING_JSON";
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Span {
+    pub name: String,
+    pub count: u32,
+    pub total: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub children: Vec<Span>,
+}
+
+impl Span {
+    const fn named(name: String) -> Span {
+        Span{ name, count: 0, total: Duration::ZERO, min: Duration::ZERO, max: Duration::ZERO, children: Vec::new() }
+    }
+
+    fn descendant(&mut self, path: &[usize]) -> &mut Span {
+        path.iter().fold(self, |span, &i| &mut span.children[i])
+    }
+
+    fn record(&mut self, elapsed: Duration) {
+        if self.count == 0 || elapsed < self.min { self.min = elapsed; }
+        if elapsed > self.max { self.max = elapsed; }
+        self.total += elapsed;
+        self.count += 1;
+    }
+
+    fn write_summary(&self, depth: usize, out: &mut String) {
+        writeln!(out, "{:indent$}{}: {}x, total {:.3?} (min {:.3?}, max {:.3?})",
+                 "", self.name, self.count, self.total, self.min, self.max, indent = depth * 2).expect("Infallible");
+        for child in &self.children {
+            child.write_summary(depth + 1, out);
+        }
+    }
+
+    fn write_json(&self, out: &mut String) {
+        write!(out, r#"{{"name":"{}","count":{},"total_ns":{},"min_ns":{},"max_ns":{},"children":"#,
+               escape_json(&self.name), self.count, self.total.as_nanos(), self.min.as_nanos(), self.max.as_nanos())
+            .expect("Infallible");
+        write_json_list(&self.children, out);
+        out.push('}');
+    }
+}
+
+fn write_json_list(spans: &[Span], out: &mut String) {
+    out.push('[');
+    for (i, span) in spans.iter().enumerate() {
+        if i > 0 { out.push(','); }
+        span.write_json(out);
+    }
+    out.push(']');
+}
+
+fn escape_json(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => ret.push_str("\\\""),
+            '\\' => ret.push_str("\\\\"),
+            c if c.is_control() => write!(ret, "\\u{:04x}", c as u32).expect("Infallible"),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Recorder {
+    // A nameless span holding the top-level spans
+    root: Span,
+    // Incremented by take(), so that spans entered before then can be ignored when they exit
+    generation: u64,
+}
+
+impl Recorder {
+    const fn new() -> Recorder {
+        Recorder{ root: Span::named(String::new()), generation: 0 }
+    }
+
+    // Returns the index of the named child of the span at parent, creating it if necessary.
+    fn enter(&mut self, parent: &[usize], name: &str) -> usize {
+        let parent = self.root.descendant(parent);
+        match parent.children.iter().position(|s| s.name == name) {
+            Some(i) => i,
+            None => {
+                parent.children.push(Span::named(name.to_string()));
+                parent.children.len() - 1
+            },
+        }
+    }
+
+    fn exit(&mut self, path: &[usize], elapsed: Duration) {
+        self.root.descendant(path).record(elapsed);
+    }
+
+    pub fn spans(&self) -> &[Span] {
+        &self.root.children
+    }
+
+    pub fn summary(&self) -> String {
+        let mut out = String::new();
+        for span in self.spans() {
+            span.write_summary(0, &mut out);
+        }
+        out
+    }
+
+    pub fn json(&self) -> String {
+        let mut out = String::new();
+        write_json_list(self.spans(), &mut out);
+        out
+    }
+}
+
+// Spans from all threads are recorded together, but each thread tracks its own current span as
+// the generation and index of each span it's inside of.
+static RECORDER: Mutex<Recorder> = Mutex::new(Recorder::new());
+thread_local! {
+    static CURRENT: RefCell<Vec<(u64, usize)>> = const { RefCell::new(Vec::new()) };
+}
+
+// The path to the innermost span entered in this generation. Spans left over from earlier
+// generations are always outermost, so they're skipped.
+fn current_path(current: &[(u64, usize)], generation: u64) -> Vec<usize> {
+    current.iter().filter(|(g, _)| *g == generation).map(|(_, i)| *i).collect()
+}
+
+// Times a span until the guard is dropped.
+#[must_use]
+pub struct Guard {
+    start: Instant,
+}
+
+pub fn span(name: &str) -> Guard {
+    CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let mut recorder = RECORDER.lock().expect("Poisoned");
+        let path = current_path(&current, recorder.generation);
+        let index = recorder.enter(&path, name);
+        current.push((recorder.generation, index));
+    });
+    Guard{ start: Instant::now() }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed();
+        CURRENT.with(|current| {
+            let mut current = current.borrow_mut();
+            let mut recorder = RECORDER.lock().expect("Poisoned");
+            // If take() was called while this span was open its parents no longer exist
+            if current.last().map(|(g, _)| *g) == Some(recorder.generation) {
+                let path = current_path(&current, recorder.generation);
+                recorder.exit(&path, elapsed);
+            }
+            current.pop();
+        });
+    }
+}
+
+// Removes and returns everything recorded so far. Spans that are still open aren't recorded.
+pub fn take() -> Recorder {
+    let mut recorder = RECORDER.lock().expect("Poisoned");
+    let generation = recorder.generation + 1;
+    std::mem::replace(&mut *recorder, Recorder{ generation, ..Recorder::new() })
+}
+
+// Prints a summary of the recorded spans to stderr, or writes them to $ADVENT_TIMING_JSON. Does
+// nothing if no spans were recorded, e.g. because the timing feature is disabled.
+pub fn report() -> anyhow::Result<()> {
+    let recorder = take();
+    if recorder.spans().is_empty() { return Ok(()); }
+    match std::env::var_os(JSON_ENV) {
+        Some(path) => std::fs::write(&path, recorder.json())?,
+        None => eprint!("\x1B[36m{}\x1B[0m", recorder.summary()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(millis: u64) -> Duration { Duration::from_millis(millis) }
+
+    fn example() -> Recorder {
+        let mut recorder = Recorder::new();
+        let a = recorder.enter(&[], "a");
+        for millis in [2, 1] {
+            let b = recorder.enter(&[a], "b");
+            recorder.exit(&[a, b], ms(millis));
+        }
+        recorder.exit(&[a], ms(5));
+        let c = recorder.enter(&[], "c \"quoted\"");
+        recorder.exit(&[c], ms(1));
+        recorder
+    }
+
+    #[test]
+    fn tree() {
+        let recorder = example();
+        assert_eq!(recorder.spans().len(), 2);
+        let a = &recorder.spans()[0];
+        assert_eq!((a.name.as_str(), a.count, a.total), ("a", 1, ms(5)));
+        let b = &a.children[0];
+        assert_eq!((b.name.as_str(), b.count, b.total, b.min, b.max), ("b", 2, ms(3), ms(1), ms(2)));
+        assert!(b.children.is_empty());
+    }
+
+    #[test]
+    fn summary() {
+        assert_eq!(example().summary(), "\
+a: 1x, total 5.000ms (min 5.000ms, max 5.000ms)
+  b: 2x, total 3.000ms (min 1.000ms, max 2.000ms)
+c \"quoted\": 1x, total 1.000ms (min 1.000ms, max 1.000ms)
+");
+    }
+
+    #[test]
+    fn json() {
+        assert_eq!(example().json(), concat!(
+            r#"[{"name":"a","count":1,"total_ns":5000000,"min_ns":5000000,"max_ns":5000000,"children":["#,
+            r#"{"name":"b","count":2,"total_ns":3000000,"min_ns":1000000,"max_ns":2000000,"children":[]}]},"#,
+            r#"{"name":"c \"quoted\"","count":1,"total_ns":1000000,"min_ns":1000000,"max_ns":1000000,"children":[]}]"#));
+    }
+
+    // Serializes the tests that call take(), since they'd otherwise clear each other's spans
+    static TAKE_LOCK: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn guards() {
+        let _lock = TAKE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        {
+            let _outer = span("guards outer");
+            for _ in 0..3 {
+                let _inner = span("guards inner");
+            }
+        }
+        let recorder = take();
+        let outer = recorder.spans().iter().find(|s| s.name == "guards outer").unwrap();
+        assert_eq!(outer.count, 1);
+        assert_eq!(outer.children.len(), 1);
+        assert_eq!(outer.children[0].count, 3);
+        assert!(outer.children[0].total <= outer.total);
+    }
+
+    #[test]
+    fn stale_guards() {
+        let _lock = TAKE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let outer = span("stale outer");
+        let middle = span("stale middle");
+        take();
+        {
+            let _inner = span("stale inner");
+        }
+        drop(middle);
+        drop(outer);
+        let _after = span("stale after");
+
+        let recorder = take();
+        let count = |name| recorder.spans().iter().find(|s| s.name == name).map(|s| s.count);
+        assert_eq!(count("stale inner"), Some(1));
+        assert_eq!(count("stale outer"), None);
+        assert_eq!(count("stale middle"), None);
+        // Still open, so it hasn't been recorded yet
+        assert_eq!(count("stale after"), Some(0));
+    }
+}
@@ -0,0 +1,11 @@
+// Verifies every registered day still produces its recorded answer (see answers.txt), so that
+// changes to the shared library can't silently break earlier days.
+use std::process::Command;
+
+#[test]
+fn recorded_answers() {
+    let res = Command::new(env!("CARGO_BIN_EXE_advent")).arg("check").output().unwrap();
+    let stdout = String::from_utf8(res.stdout).unwrap();
+    let stderr = String::from_utf8(res.stderr).unwrap();
+    assert!(res.status.success(), "{}{}", stdout, stderr);
+}